@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::input::Source;
+use crate::solver::Day;
+use crate::solver::Timings;
+
+/// Number of times each day is solved when no run count is given
+pub const DEFAULT_RUNS: usize = 10;
+
+/// A phase is flagged as a regression when its median exceeds the baseline's by this ratio
+const REGRESSION_THRESHOLD: f64 = 1.1;
+
+const PHASES: [&str; 3] = ["parse", "part 1", "part 2"];
+
+/// Fastest, median and slowest run time of a phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to compute stats from");
+
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Run time stats of a day, for each phase
+#[derive(Debug, Clone, Copy)]
+pub struct Bench {
+    pub day: u32,
+    pub parse: Stats,
+    pub part_1: Stats,
+    pub part_2: Stats,
+}
+
+impl Bench {
+    fn phases(&self) -> [Stats; 3] {
+        [self.parse, self.part_1, self.part_2]
+    }
+
+    fn medians(&self) -> Timings {
+        Timings {
+            parse: self.parse.median,
+            part_1: self.part_1.median,
+            part_2: self.part_2.median,
+        }
+    }
+}
+
+/// Solves `day` over `input` `runs` times
+pub fn bench(day: &Day, input: &str, runs: usize) -> anyhow::Result<Bench> {
+    anyhow::ensure!(runs > 0, "can't benchmark with zero runs");
+
+    let mut timings = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let (_, run_timings) = day.run(input)?;
+        timings.push(run_timings);
+    }
+
+    Ok(Bench {
+        day: day.number,
+        parse: Stats::new(timings.iter().map(|t| t.parse).collect()),
+        part_1: Stats::new(timings.iter().map(|t| t.part_1).collect()),
+        part_2: Stats::new(timings.iter().map(|t| t.part_2).collect()),
+    })
+}
+
+/// Benchmarks each of `days` over its input from `source` and prints the stats, compared against
+/// the baseline at `baseline` if given, and saves them as a baseline at `save_baseline` if given.
+/// Days that fail are reported and skipped. Returns `false` if any of them failed.
+pub fn run(
+    days: &[&Day],
+    source: &Source,
+    runs: usize,
+    baseline: Option<&Path>,
+    save_baseline: Option<&Path>,
+) -> anyhow::Result<bool> {
+    let baseline = baseline.map(Baseline::load).transpose()?;
+
+    let mut benches = vec![];
+    let mut failed = false;
+    for day in days {
+        match source
+            .read(day.number)
+            .and_then(|input| bench(day, &input, runs))
+        {
+            Ok(bench) => benches.push(bench),
+            Err(err) => {
+                println!("Day {} failed: {err:#}", day.number);
+                failed = true;
+            }
+        }
+    }
+
+    print(&benches, baseline.as_ref());
+
+    if let Some(path) = save_baseline {
+        Baseline::save(&benches, path)?;
+    }
+
+    Ok(!failed)
+}
+
+/// Median run times of a previous benchmark, for each day
+#[derive(Debug, Default)]
+pub struct Baseline(BTreeMap<u32, Timings>);
+
+impl Baseline {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("couldn't read baseline `{}`", path.display()))?;
+
+        let days = serde_json::from_str::<BTreeMap<u32, [u64; 3]>>(&content)
+            .with_context(|| format!("invalid baseline `{}`", path.display()))?;
+
+        Ok(Self(
+            days.into_iter()
+                .map(|(day, [parse, part_1, part_2])| {
+                    let timings = Timings {
+                        parse: Duration::from_nanos(parse),
+                        part_1: Duration::from_nanos(part_1),
+                        part_2: Duration::from_nanos(part_2),
+                    };
+                    (day, timings)
+                })
+                .collect(),
+        ))
+    }
+
+    /// Saves the medians of `benches` to `path`, as a map of day to parse, part 1 and part 2
+    /// nanoseconds
+    pub fn save(benches: &[Bench], path: &Path) -> anyhow::Result<()> {
+        let days = benches
+            .iter()
+            .map(|bench| {
+                let medians = bench.medians();
+                let nanos = [medians.parse, medians.part_1, medians.part_2]
+                    .map(|median| median.as_nanos() as u64);
+                (bench.day, nanos)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        std::fs::write(path, serde_json::to_string_pretty(&days)?)
+            .with_context(|| format!("couldn't write baseline `{}`", path.display()))
+    }
+
+    fn get(&self, day: u32) -> Option<[Duration; 3]> {
+        self.0
+            .get(&day)
+            .map(|timings| [timings.parse, timings.part_1, timings.part_2])
+    }
+}
+
+/// Prints the stats of every phase of `benches`, compared against `baseline` if given, followed
+/// by a summary
+pub fn print(benches: &[Bench], baseline: Option<&Baseline>) {
+    let mut regressions = 0;
+
+    println!(
+        "{:>5} | {:<6} | {:>10} | {:>10} | {:>10} | baseline",
+        "day", "phase", "min", "median", "max"
+    );
+    println!(
+        "{:->5}-+-{:-<6}-+-{:->10}-+-{:->10}-+-{:->10}-+-{:-<20}",
+        "", "", "", "", "", ""
+    );
+
+    for bench in benches {
+        let baseline = baseline.and_then(|b| b.get(bench.day));
+
+        for (idx, (phase, stats)) in PHASES.iter().zip(bench.phases()).enumerate() {
+            let day = if idx == 0 {
+                bench.day.to_string()
+            } else {
+                String::new()
+            };
+
+            let comparison = match baseline {
+                Some(baseline) => {
+                    let ratio = stats.median.as_secs_f64() / baseline[idx].as_secs_f64();
+                    let change = (ratio - 1.0) * 100.0;
+
+                    if ratio > REGRESSION_THRESHOLD {
+                        regressions += 1;
+                        format!("{change:+.1}% REGRESSION")
+                    } else {
+                        format!("{change:+.1}%")
+                    }
+                }
+                None => "-".to_string(),
+            };
+
+            println!(
+                "{day:>5} | {phase:<6} | {:>10.2?} | {:>10.2?} | {:>10.2?} | {comparison}",
+                stats.min, stats.median, stats.max
+            );
+        }
+    }
+
+    let total =
+        benches
+            .iter()
+            .map(|bench| bench.medians())
+            .fold(Timings::default(), |acc, medians| Timings {
+                parse: acc.parse + medians.parse,
+                part_1: acc.part_1 + medians.part_1,
+                part_2: acc.part_2 + medians.part_2,
+            });
+
+    println!();
+    println!(
+        "Total of medians: {:.2?} (parse {:.2?}, part 1 {:.2?}, part 2 {:.2?})",
+        total.total(),
+        total.parse,
+        total.part_1,
+        total.part_2
+    );
+
+    if let Some(slowest) = benches.iter().max_by_key(|bench| bench.medians().total()) {
+        println!(
+            "Slowest day: {} ({:.2?})",
+            slowest.day,
+            slowest.medians().total()
+        );
+    }
+
+    if regressions > 0 {
+        println!(
+            "{regressions} phase(s) regressed by more than {:.0}% against the baseline",
+            (REGRESSION_THRESHOLD - 1.0) * 100.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            Stats::new(vec![ms(3), ms(1), ms(2)]),
+            Stats {
+                min: ms(1),
+                median: ms(2),
+                max: ms(3),
+            }
+        );
+
+        assert_eq!(
+            Stats::new(vec![ms(4), ms(1), ms(2), ms(10)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(10),
+            }
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Cycle detection for simulations that step from one state to the next and eventually loop.
+//! None of these return if the states never repeat.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Shape of a looping sequence of states: after the first `prefix` states, the next `period`
+/// ones repeat forever
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Index of the first state equal to state `n`, which is always below `prefix + period`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Finds the cycle using Brent's algorithm, which only keeps two states around
+///
+/// See <https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm>
+pub fn brent<S: PartialEq + Clone>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the period by moving the tortoise up to the hare at every power of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle {
+        prefix: find_prefix(start, &mut step, period),
+        period,
+    }
+}
+
+/// Finds the cycle using Floyd's algorithm, moving a hare twice as fast as a tortoise
+///
+/// See <https://en.wikipedia.org/wiki/Cycle_detection#Floyd's_tortoise_and_hare>
+pub fn floyd<S: PartialEq + Clone>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+
+    // The tortoise has gone round a whole number of periods, so now measure one of them
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle {
+        prefix: find_prefix(start, &mut step, period),
+        period,
+    }
+}
+
+/// Finds the cycle by remembering every state, which takes more memory than [`brent`] but only
+/// steps through each state once. Also returns those states, in order.
+pub fn with_history<S: Hash + Eq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+) -> (Cycle, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut curr = start;
+
+    loop {
+        if let Some(&prefix) = seen.get(&curr) {
+            let period = states.len() - prefix;
+            return (Cycle { prefix, period }, states);
+        }
+
+        let next = step(&curr);
+        seen.insert(curr.clone(), states.len());
+        states.push(curr);
+        curr = next;
+    }
+}
+
+/// State after stepping `n` times from `start`, for any `n`: only steps through the states
+/// until they loop, using [`brent`]
+pub fn nth<S: PartialEq + Clone>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(&start, &mut step);
+
+    (0..cycle.reduce(n)).fold(start, |curr, _| step(&curr))
+}
+
+/// Same as [`nth`], but using [`with_history`]
+pub fn nth_hashed<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (cycle, mut states) = with_history(start, step);
+
+    states.swap_remove(cycle.reduce(n))
+}
+
+/// Number of steps before the cycle starts, knowing its period: a hare that's a period ahead of
+/// the tortoise meets it right at the start of the cycle
+fn find_prefix<S: PartialEq + Clone>(
+    start: &S,
+    step: &mut impl FnMut(&S) -> S,
+    period: usize,
+) -> usize {
+    let mut tortoise = start.clone();
+    let mut hare = (0..period).fold(start.clone(), |hare, _| step(&hare));
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, 3, 4, 5, 2, 3, 4, 5, 2...
+    fn step(x: &usize) -> usize {
+        if *x < 5 {
+            x + 1
+        } else {
+            2
+        }
+    }
+
+    #[test]
+    fn finders_agree() {
+        let expected = Cycle {
+            prefix: 2,
+            period: 4,
+        };
+
+        assert_eq!(brent(&0, step), expected);
+        assert_eq!(floyd(&0, step), expected);
+        assert_eq!(with_history(0, step), (expected, vec![0, 1, 2, 3, 4, 5]));
+
+        // Starting inside the cycle, or on a fixed point
+        let expected = Cycle {
+            prefix: 0,
+            period: 4,
+        };
+        assert_eq!(brent(&3, step), expected);
+        assert_eq!(floyd(&3, step), expected);
+        assert_eq!(with_history(3, step).0, expected);
+        assert_eq!(brent(&7, |x| *x).period, 1);
+        assert_eq!(floyd(&7, |x| *x).prefix, 0);
+    }
+
+    #[test]
+    fn far_states() {
+        assert_eq!(nth(0, step, 1), 1);
+        assert_eq!(nth(0, step, 10), 2);
+        assert_eq!(nth(0, step, 1_000_000_000_000), 4);
+        assert_eq!(nth_hashed(0, step, 1_000_000_000_000), 4);
+        assert_eq!(nth_hashed(0, step, 5), 5);
+    }
+}
@@ -1,31 +1,43 @@
 use anyhow::Context;
 
-pub fn solve() -> anyhow::Result<()> {
-    let text = std::fs::read_to_string("res/day01.txt")?;
+use crate::diag;
+use crate::solver::Solver;
 
-    let mut part_1 = 0;
-    let mut part_2 = 0;
+pub struct Day1;
 
-    for line in text.lines() {
-        let first = line
-            .chars()
-            .find(|c| c.is_numeric())
-            .context("no number found")?
-            .to_digit(10)
-            .context("non-numberical character")?;
+impl Solver for Day1 {
+    type Input = Vec<String>;
 
-        let last = line
-            .chars()
-            .rev()
-            .find(|c| c.is_numeric())
-            .context("no number found")?
-            .to_digit(10)
-            .context("non-numberical character")?;
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_1 = 0;
+
+        for line in input.iter() {
+            let first = line
+                .chars()
+                .find(|c| c.is_numeric())
+                .context("no number found")?
+                .to_digit(10)
+                .context("non-numberical character")?;
 
-        let number = first * 10 + last;
+            let last = line
+                .chars()
+                .rev()
+                .find(|c| c.is_numeric())
+                .context("no number found")?
+                .to_digit(10)
+                .context("non-numberical character")?;
+
+            part_1 += first * 10 + last;
+        }
 
-        part_1 += number;
+        Ok(part_1)
+    }
 
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
         const STRUMBERS: [(&str, u32); 18] = [
             ("1", 1),
             ("2", 2),
@@ -47,45 +59,79 @@ pub fn solve() -> anyhow::Result<()> {
             ("nine", 9),
         ];
 
-        let rev_line = line.chars().rev().collect::<String>();
-        let mut first = None;
-        let mut last = None;
-
-        for (strumber, number) in STRUMBERS {
-            if let Some(index) = line.find(strumber) {
-                if first
-                    .map(|(_curr_number, curr_index)| curr_index > index)
-                    .unwrap_or(true)
-                {
-                    first = Some((number, index));
+        let mut part_2 = 0;
+
+        for line in input.iter() {
+            let rev_line = line.chars().rev().collect::<String>();
+            let mut first = None;
+            let mut last = None;
+
+            for (strumber, number) in STRUMBERS {
+                if let Some(index) = line.find(strumber) {
+                    if first
+                        .map(|(_curr_number, curr_index)| curr_index > index)
+                        .unwrap_or(true)
+                    {
+                        first = Some((number, index));
+                    }
                 }
-            }
 
-            let rev_strumber: String = strumber.chars().rev().collect();
-            if let Some(index) = rev_line.find(&rev_strumber) {
-                if last
-                    .map(|(_, curr_index)| curr_index > index)
-                    .unwrap_or(true)
-                {
-                    last = Some((number, index));
+                let rev_strumber: String = strumber.chars().rev().collect();
+                if let Some(index) = rev_line.find(&rev_strumber) {
+                    if last
+                        .map(|(_, curr_index)| curr_index > index)
+                        .unwrap_or(true)
+                    {
+                        last = Some((number, index));
+                    }
                 }
             }
-        }
 
-        let first = first.context("first digit not found");
+            let first = first.context("first digit not found");
 
-        match first {
-            Ok(first) => part_2 += first.0 * 10,
-            Err(err) => eprintln!("{err}"),
-        }
+            match first {
+                Ok(first) => part_2 += first.0 * 10,
+                Err(err) => diag!("{err}"),
+            }
 
-        match last {
-            Some((last, _)) => part_2 += last,
-            None => eprintln!("last digit not found"),
+            match last {
+                Some((last, _)) => part_2 += last,
+                None => diag!("last digit not found"),
+            }
         }
+
+        Ok(part_2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part_1() {
+        const EXAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+        let input = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part_1(&input).unwrap().to_string(), "142");
     }
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+    #[test]
+    fn example_part_2() {
+        const EXAMPLE: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
 
-    Ok(())
+        let input = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part_2(&input).unwrap().to_string(), "281");
+    }
 }
@@ -1,82 +1,146 @@
+use crate::manhattan::polygon::interior_points;
 use crate::manhattan::step;
 use crate::manhattan::Direction;
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+use anyhow::Context;
+use palette::named;
 use std::collections::HashSet;
 
-#[allow(clippy::needless_range_loop)]
-pub fn solve() -> anyhow::Result<()> {
-    let mut map: Vec<Vec<_>> = std::fs::read_to_string("res/day10.txt")?
-        .lines()
-        .map(|l| l.chars().collect())
-        .collect();
-
-    let mut start = (0, 0);
-    'outer: for i in 0..map.len() {
-        for j in 0..map[i].len() {
-            if map[i][j] == 'S' {
-                start = (i as isize, j as isize);
-                // input specific
-                map[i][j] = '|';
-                break 'outer;
-            }
-        }
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Maze;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let map = Grid::parse_with(input, |c| match c {
+            '.' | 'S' => Ok(c),
+            c => Pipe::try_from(c).map(|_| c),
+        })?;
+
+        let start = map
+            .position(|c| *c == 'S')
+            .context("couldn't find the starting tile")?;
+
+        let mut maze = Maze {
+            map,
+            start,
+            start_pipe: Pipe::Vertical,
+        };
+
+        // The starting pipe is the one connecting to both of its connecting neighbours
+        let connected = [
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+        ]
+        .into_iter()
+        .filter(|dir| {
+            maze.pipe(step(start, *dir))
+                .map(|pipe| pipe.dirs().contains(&dir.u_turn()))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+
+        maze.start_pipe = Pipe::ALL
+            .into_iter()
+            .find(|pipe| pipe.dirs().iter().all(|dir| connected.contains(dir)))
+            .context("the starting tile doesn't connect to exactly two pipes")?;
+
+        Ok(maze)
     }
 
-    // input specific
-    let prev = step(start, Direction::North);
-    // input specific
-    let mut curr = step(prev, Direction::North);
-    // input specific
-    let mut left_tiles = HashSet::from([step(start, Direction::East), step(prev, Direction::East)]);
-    // input specific
-    let mut right_tiles =
-        HashSet::from([step(start, Direction::West), step(prev, Direction::West)]);
-
-    let mut loop_tiles = HashSet::from([prev, curr]);
-
-    while curr != start {
-        let pipe = Pipe::try_from(map[curr.0 as usize][curr.1 as usize])?;
-        for (dir, left, right) in pipe.dirs() {
-            let next = step(curr, *dir);
-            if !loop_tiles.contains(&next) {
-                loop_tiles.insert(next);
-                left.iter().for_each(|left| {
-                    left_tiles.insert(step(curr, *left));
-                });
-                right.iter().for_each(|right| {
-                    right_tiles.insert(step(curr, *right));
-                });
-                curr = next;
-                break;
-            }
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(trace(input)?.len() / 2)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let loop_tiles = trace(input)?;
+
+        render::show("Loop and enclosed tiles:", || draw(input, &loop_tiles));
+
+        Ok(interior_points(&loop_tiles)?)
+    }
+}
+
+pub struct Maze {
+    map: Grid<char>,
+    start: Position,
+    start_pipe: Pipe,
+}
+
+impl Maze {
+    fn pipe(&self, pos: Position) -> anyhow::Result<Pipe> {
+        if pos == self.start {
+            return Ok(self.start_pipe);
         }
+
+        let tile = self.map.get(pos).context("stepped out of the map")?;
+
+        Pipe::try_from(*tile).map_err(|err| anyhow::anyhow!("{} at {pos:?}", err.message))
     }
+}
 
-    left_tiles.retain(|t| !loop_tiles.contains(t));
-    right_tiles.retain(|t| !loop_tiles.contains(t));
-
-    for i in 0..map.len() as isize {
-        for j in 0..map[0].len() as isize {
-            if loop_tiles.contains(&(i, j)) {
-                print!("🟩")
-            } else if left_tiles.contains(&(i, j)) {
-                print!("🟥");
-            } else if right_tiles.contains(&(i, j)) {
-                print!("🟦");
-            } else {
-                print!("⬛️");
-            }
+/// Draws the loop with box-drawing characters, telling the tiles it encloses from the ones outside
+/// it by counting the pipes crossed on the way to them along their row
+fn draw(maze: &Maze, loop_tiles: &[Position]) -> Picture {
+    let on_loop = loop_tiles.iter().collect::<HashSet<_>>();
+    let enclosed = Cell::new('I').fg(named::GOLD);
+    let outside = Cell::new('·').fg(named::DIMGRAY);
+
+    let mut inside = false;
+    Picture::new(&maze.map, |pos, _| {
+        if pos.1 == 0 {
+            inside = false;
+        }
+
+        if !on_loop.contains(&pos) {
+            return if inside { enclosed } else { outside };
+        }
+
+        // Tracing the loop already checked its tiles are pipes
+        let pipe = maze.pipe(pos).unwrap_or(Pipe::Horizontal);
+        if pipe.dirs().contains(&Direction::North) {
+            inside = !inside;
         }
-        println!();
+
+        Cell::new(pipe.into()).fg(named::LIMEGREEN)
+    })
+    .legend(Cell::new('│').fg(named::LIMEGREEN), "loop")
+    .legend(enclosed, "enclosed by the loop")
+    .legend(outside, "outside the loop")
+}
+
+/// Walks the loop from the starting tile, returning its tiles in order
+fn trace(maze: &Maze) -> anyhow::Result<Vec<Position>> {
+    let mut loop_tiles = vec![maze.start];
+    let mut dir = maze.start_pipe.dirs()[0];
+    let mut curr = step(maze.start, dir);
+
+    while curr != maze.start {
+        loop_tiles.push(curr);
+
+        let came_from = dir.u_turn();
+        dir = maze
+            .pipe(curr)?
+            .dirs()
+            .into_iter()
+            .find(|dir| *dir != came_from)
+            .context("pipe leads back where it came from")?;
+
+        curr = step(curr, dir);
     }
 
-    println!(
-        "Part 1: {}\nPart 2: {}",
-        loop_tiles.len() / 2,
-        left_tiles.len() + 108, // input specific
-    );
-    Ok(())
+    Ok(loop_tiles)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Pipe {
     /// a vertical pipe connecting north and south.
     Vertical,
@@ -92,8 +156,22 @@ enum Pipe {
     BendSouthWest,
 }
 
+/// Box-drawing character showing the pipe
+impl From<Pipe> for char {
+    fn from(pipe: Pipe) -> Self {
+        match pipe {
+            Pipe::Vertical => '│',
+            Pipe::Horizontal => '─',
+            Pipe::BendNorthEast => '└',
+            Pipe::BendNorthWest => '┘',
+            Pipe::BendSouthWest => '┐',
+            Pipe::BendSouthEast => '┌',
+        }
+    }
+}
+
 impl TryFrom<char> for Pipe {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         Ok(match value {
@@ -103,41 +181,109 @@ impl TryFrom<char> for Pipe {
             'J' => Self::BendNorthWest,
             '7' => Self::BendSouthWest,
             'F' => Self::BendSouthEast,
-            '.' => anyhow::bail!("this is not a pipe"),
-            'S' => anyhow::bail!("you are supposed to replace the S manually"),
-            c => anyhow::bail!("unexpected character `{c}`"),
+            '.' => return Err(ParseError::char(value, "this is not a pipe")),
+            'S' => {
+                return Err(ParseError::char(
+                    value,
+                    "the starting tile's pipe is unknown",
+                ))
+            }
+            c => return Err(ParseError::char(c, format!("unexpected character `{c}`"))),
         })
     }
 }
 
 impl Pipe {
-    // Valid directions, with their respective left and right directions
-    fn dirs(&self) -> &[(Direction, &[Direction], &[Direction])] {
+    const ALL: [Self; 6] = [
+        Self::Vertical,
+        Self::Horizontal,
+        Self::BendNorthEast,
+        Self::BendNorthWest,
+        Self::BendSouthEast,
+        Self::BendSouthWest,
+    ];
+
+    /// The two directions the pipe connects
+    fn dirs(&self) -> [Direction; 2] {
         match self {
-            Self::Vertical => &[
-                (Direction::North, &[Direction::West], &[Direction::East]),
-                (Direction::South, &[Direction::East], &[Direction::West]),
-            ],
-            Self::Horizontal => &[
-                (Direction::East, &[Direction::North], &[Direction::South]),
-                (Direction::West, &[Direction::South], &[Direction::North]),
-            ],
-            Self::BendNorthEast => &[
-                (Direction::North, &[Direction::South, Direction::West], &[]),
-                (Direction::East, &[], &[Direction::South, Direction::West]),
-            ],
-            Self::BendNorthWest => &[
-                (Direction::North, &[], &[Direction::East, Direction::South]),
-                (Direction::West, &[Direction::East, Direction::South], &[]),
-            ],
-            Self::BendSouthWest => &[
-                (Direction::South, &[Direction::North, Direction::East], &[]),
-                (Direction::West, &[], &[Direction::North, Direction::East]),
-            ],
-            Self::BendSouthEast => &[
-                (Direction::South, &[], &[Direction::North, Direction::West]),
-                (Direction::East, &[Direction::North, Direction::West], &[]),
-            ],
+            Self::Vertical => [Direction::North, Direction::South],
+            Self::Horizontal => [Direction::East, Direction::West],
+            Self::BendNorthEast => [Direction::North, Direction::East],
+            Self::BendNorthWest => [Direction::North, Direction::West],
+            Self::BendSouthWest => [Direction::South, Direction::West],
+            Self::BendSouthEast => [Direction::South, Direction::East],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part_1() {
+        const SQUARE: &str = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....";
+        const COMPLEX: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+        let input = Day10::parse(SQUARE).unwrap();
+        assert_eq!(Day10::part_1(&input).unwrap().to_string(), "4");
+
+        let input = Day10::parse(COMPLEX).unwrap();
+        assert_eq!(Day10::part_1(&input).unwrap().to_string(), "8");
+    }
+
+    #[test]
+    fn example_part_2() {
+        const SQUEEZE: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+        const LARGER: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+        const JUNK: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+
+        let input = Day10::parse(SQUEEZE).unwrap();
+        assert_eq!(Day10::part_2(&input).unwrap().to_string(), "4");
+
+        let input = Day10::parse(LARGER).unwrap();
+        assert_eq!(Day10::part_2(&input).unwrap().to_string(), "8");
+
+        let input = Day10::parse(JUNK).unwrap();
+        assert_eq!(Day10::part_2(&input).unwrap().to_string(), "10");
+    }
+}
@@ -1,34 +1,40 @@
 use std::collections::HashMap;
 
-use anyhow::Context;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day12.txt")?;
+pub struct Day12;
 
-    let mut cache = HashMap::new();
-
-    let mut part_1_input = vec![];
-    let mut part_2_input = vec![];
+impl Solver for Day12 {
+    type Input = Vec<(Vec<Spring>, Vec<Pattern>)>;
 
-    for line in input.lines() {
-        part_1_input.append(&mut parse_line(line, 1)?);
-        part_2_input.append(&mut parse_line(line, 5)?);
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse::lines(input, parse_line)?)
     }
 
-    let mut part_1 = 0;
-    let mut part_2 = 0;
-
-    for (springs, patterns) in part_1_input.iter() {
-        part_1 += combinations(springs, patterns, &mut cache);
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(arrangements(input, 1))
     }
 
-    for (springs, patterns) in part_2_input.iter() {
-        part_2 += combinations(springs, patterns, &mut cache);
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(arrangements(input, 5))
     }
+}
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+/// Sum of possible arrangements of all records, each unfolded `repeat` times
+fn arrangements(records: &[(Vec<Spring>, Vec<Pattern>)], repeat: usize) -> usize {
+    let unfolded = records
+        .iter()
+        .flat_map(|(springs, patterns)| unfold(springs, patterns, repeat))
+        .collect::<Vec<_>>();
 
-    Ok(())
+    let mut cache = HashMap::new();
+
+    unfolded
+        .iter()
+        .map(|(springs, patterns)| combinations(springs, patterns, &mut cache))
+        .sum()
 }
 
 fn combinations<'a, 'b: 'a>(
@@ -80,45 +86,47 @@ fn combinations<'a, 'b: 'a>(
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Spring {
+pub enum Spring {
     Operational,
     Broken,
     Unknown,
 }
 
 impl TryFrom<char> for Spring {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         Ok(match value {
             '.' => Self::Operational,
             '#' => Self::Broken,
             '?' => Self::Unknown,
-            v => anyhow::bail!("unexpected spring character `{v}`"),
+            v => {
+                return Err(ParseError::char(
+                    v,
+                    format!("unexpected spring character `{v}`"),
+                ))
+            }
         })
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Pattern {
+pub enum Pattern {
     Operational,
     Broken(usize),
 }
 
-fn parse_line(line: &str, repeat: usize) -> anyhow::Result<Vec<(Vec<Spring>, Vec<Pattern>)>> {
-    let mut split = line.split_whitespace();
+fn parse_line(line: &str) -> Result<(Vec<Spring>, Vec<Pattern>), ParseError> {
+    let (records, pattern) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::end(line, "no pattern"))?;
 
-    // Parse
-    let springs = split
-        .next()
-        .context("no records")?
-        .chars()
-        .map(TryFrom::try_from)
-        .collect::<anyhow::Result<Vec<Spring>>>()?;
+    let springs =
+        parse::each_char(records, Spring::try_from).map_err(|err| err.within(line, records))?;
 
     let mut patterns = vec![];
-    for n in split.next().context("no pattern")?.split(',') {
-        let number = n.parse()?;
+    for n in pattern.split(',') {
+        let number = parse::number(n).map_err(|err| err.within(line, n))?;
         if patterns.is_empty() {
             patterns.push(Pattern::Broken(number));
         } else {
@@ -127,6 +135,14 @@ fn parse_line(line: &str, repeat: usize) -> anyhow::Result<Vec<(Vec<Spring>, Vec
         }
     }
 
+    Ok((springs, patterns))
+}
+
+fn unfold(
+    springs: &[Spring],
+    patterns: &[Pattern],
+    repeat: usize,
+) -> Vec<(Vec<Spring>, Vec<Pattern>)> {
     // Repeat
     let mut repeated_springs = Vec::with_capacity(springs.len() * (repeat + 1));
     let mut repeated_patterns = Vec::with_capacity(patterns.len() * (repeat + 1));
@@ -135,8 +151,8 @@ fn parse_line(line: &str, repeat: usize) -> anyhow::Result<Vec<(Vec<Spring>, Vec
             repeated_springs.push(Spring::Unknown);
             repeated_patterns.push(Pattern::Operational);
         }
-        repeated_springs.extend(springs.clone());
-        repeated_patterns.extend(patterns.clone());
+        repeated_springs.extend(springs);
+        repeated_patterns.extend(patterns);
     }
 
     let springs = repeated_springs;
@@ -160,5 +176,26 @@ fn parse_line(line: &str, repeat: usize) -> anyhow::Result<Vec<(Vec<Spring>, Vec
     patterns.push(Pattern::Operational);
     ret.push((springs.clone(), patterns.clone()));
 
-    Ok(ret)
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn example() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day12::part_1(&input).unwrap().to_string(), "21");
+        assert_eq!(Day12::part_2(&input).unwrap().to_string(), "525152");
+    }
 }
@@ -1,59 +1,107 @@
-pub fn solve() -> anyhow::Result<()> {
-    let content = std::fs::read_to_string("res/day13.txt")?;
-
-    let mut part_1 = 0;
-    let mut part_2 = 0;
-    for pattern_str in content.split("\n\n") {
-        let pattern = pattern_str.lines().map(|l| l.chars().collect()).collect();
-        if let Some(line) = find_reflection_line(&pattern, 0) {
-            part_1 += 100 * line;
-            println!("reflection between lines {} and {line}", line - 1);
-        } else if let Some(column) = find_reflection_column(&pattern, 0) {
-            part_1 += column;
-            println!("reflection between columns {} and {column}", column - 1);
-        } else {
-            anyhow::bail!("no reflection line found for {pattern:?}");
-        }
+use crate::diag;
+use crate::manhattan::Grid;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
+
+pub struct Day13;
+
+impl Solver for Day13 {
+    type Input = Vec<Grid<char>>;
 
-        if let Some(line) = find_reflection_line(&pattern, 1) {
-            part_2 += 100 * line;
-        } else if let Some(column) = find_reflection_column(&pattern, 1) {
-            part_2 += column;
-        } else {
-            anyhow::bail!("no off-by-1 reflection line found for {pattern:?}");
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let patterns = parse::blocks(input, |pattern| {
+            Grid::parse_with(pattern, |c| match c {
+                '.' | '#' => Ok(c),
+                c => Err(ParseError::char(c, format!("unexpected tile `{c}`"))),
+            })
+        })?;
+
+        Ok(patterns)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_1 = 0;
+        for pattern in input.iter() {
+            if let Some(line) = find_reflection_line(pattern, 0) {
+                part_1 += 100 * line;
+                diag!("reflection between lines {} and {line}", line - 1);
+            } else if let Some(column) = find_reflection_column(pattern, 0) {
+                part_1 += column;
+                diag!("reflection between columns {} and {column}", column - 1);
+            } else {
+                anyhow::bail!("no reflection line found for\n{pattern}");
+            }
         }
+
+        Ok(part_1)
     }
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_2 = 0;
+        for pattern in input.iter() {
+            if let Some(line) = find_reflection_line(pattern, 1) {
+                part_2 += 100 * line;
+            } else if let Some(column) = find_reflection_column(pattern, 1) {
+                part_2 += column;
+            } else {
+                anyhow::bail!("no off-by-1 reflection line found for\n{pattern}");
+            }
+        }
+
+        Ok(part_2)
+    }
 }
 
-fn check_reflection_line(line: usize, pattern: &[Vec<char>]) -> usize {
+fn check_reflection_line(line: usize, pattern: &Grid<char>) -> usize {
     (0..line)
         .rev()
-        .zip(line..pattern.len())
+        .zip(line..pattern.height())
         .map(|(i, j)| {
-            pattern[i]
+            pattern
+                .row(i)
                 .iter()
-                .zip(pattern[j].iter())
+                .zip(pattern.row(j))
                 .filter(|(i, j)| i != j)
                 .count()
         })
         .sum()
 }
 
-fn find_reflection_line(pattern: &Vec<Vec<char>>, invalid_chars: usize) -> Option<usize> {
-    (1..pattern.len()).find(|&line| check_reflection_line(line, pattern) == invalid_chars)
+fn find_reflection_line(pattern: &Grid<char>, invalid_chars: usize) -> Option<usize> {
+    (1..pattern.height()).find(|&line| check_reflection_line(line, pattern) == invalid_chars)
 }
 
-fn check_reflection_column(column: usize, pattern: &[Vec<char>]) -> usize {
-    (0..column)
-        .rev()
-        .zip(column..pattern[0].len())
-        .map(|(i, j)| pattern.iter().filter(|line| line[i] != line[j]).count())
-        .sum()
+fn find_reflection_column(pattern: &Grid<char>, invalid_chars: usize) -> Option<usize> {
+    find_reflection_line(&pattern.transpose(), invalid_chars)
 }
 
-fn find_reflection_column(pattern: &[Vec<char>], invalid_chars: usize) -> Option<usize> {
-    (1..pattern[0].len()).find(|&column| check_reflection_column(column, pattern) == invalid_chars)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn example() {
+        let input = Day13::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day13::part_1(&input).unwrap().to_string(), "405");
+        assert_eq!(Day13::part_2(&input).unwrap().to_string(), "400");
+    }
 }
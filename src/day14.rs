@@ -1,176 +1,140 @@
-#![allow(clippy::needless_range_loop)] // hush now clippy
-
-use std::collections::HashMap;
-
-use anyhow::Context;
-
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day14.txt")?;
-
-    let mut platform = input
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect::<Vec<Vec<_>>>();
-
-    let mut history = HashMap::new();
-
-    tilt_north(&mut platform)?;
-
-    let part_1 = calculate_load(&platform)?;
-
-    tilt_west(&mut platform)?;
-    tilt_south(&mut platform)?;
-    tilt_east(&mut platform)?;
-
-    let mut completed_cycles = 1;
+use palette::named;
+
+use crate::cycle;
+use crate::manhattan::Grid;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+
+pub struct Day14;
+
+impl Solver for Day14 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let platform = Grid::parse_with(input, |c| match c {
+            'O' | '#' | '.' => Ok(c),
+            c => Err(ParseError::char(
+                c,
+                format!("unexpected tile character `{c}`"),
+            )),
+        })?;
+
+        Ok(platform)
+    }
 
-    while !history.contains_key(&platform) {
-        history.insert(platform.clone(), completed_cycles);
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut platform = input.clone();
 
-        tilt_north(&mut platform)?;
-        tilt_west(&mut platform)?;
-        tilt_south(&mut platform)?;
-        tilt_east(&mut platform)?;
+        tilt_north(&mut platform);
+        render::show("Tilted north:", || draw(&platform));
 
-        completed_cycles += 1;
+        calculate_load(&platform)
     }
 
-    let step = completed_cycles
-        - history
-            .get(&platform)
-            .context("we should have a loop by now")?;
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let platform = cycle::nth_hashed(input.clone(), spin_cycle, 1_000_000_000);
+        render::show("After 1000000000 spin cycles:", || draw(&platform));
 
-    while completed_cycles + step < 1_000_000_000 {
-        completed_cycles += step;
+        calculate_load(&platform)
     }
+}
 
-    for _ in 0..1_000_000_000 - completed_cycles {
-        tilt_north(&mut platform)?;
-        tilt_west(&mut platform)?;
-        tilt_south(&mut platform)?;
-        tilt_east(&mut platform)?;
+/// Tilts the platform north, west, south and east in turn: tilting north and then rotating it
+/// clockwise, four times over
+fn spin_cycle(platform: &Grid<char>) -> Grid<char> {
+    let mut platform = platform.clone();
+
+    for _ in 0..4 {
+        tilt_north(&mut platform);
+        platform = platform.rotate_clockwise();
     }
 
-    let part_2 = calculate_load(&platform)?;
+    platform
+}
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+/// Draws the rocks, shading the round ones by how much load they put on the north support beams
+fn draw(platform: &Grid<char>) -> Picture {
+    let height = platform.height() as f32;
+
+    Picture::new(platform, |(i, _), tile| match tile {
+        'O' => Cell::new('O').fg(render::gradient(1.0 - i as f32 / height)),
+        '#' => Cell::new('#').fg(named::SLATEGRAY),
+        _ => Cell::new('·').fg(named::DIMGRAY),
+    })
+    .legend(
+        Cell::new('O').fg(render::gradient(1.0)),
+        "round rock, heaviest load",
+    )
+    .legend(
+        Cell::new('O').fg(render::gradient(0.0)),
+        "round rock, lightest load",
+    )
+    .legend(Cell::new('#').fg(named::SLATEGRAY), "cube-shaped rock")
 }
 
-fn calculate_load(platform: &[Vec<char>]) -> anyhow::Result<usize> {
+fn calculate_load(platform: &Grid<char>) -> anyhow::Result<usize> {
     let mut load = 0;
 
-    for i in 0..platform.len() {
-        for j in 0..platform[i].len() {
-            match platform[i][j] {
-                'O' => load += platform.len() - i,
+    for ((i, _), tile) in platform.iter() {
+        match tile {
+            'O' => load += platform.height() - i as usize,
 
-                '#' | '.' => {}
+            '#' | '.' => {}
 
-                c => anyhow::bail!("unexpected tile character `{c}`"),
-            }
+            c => anyhow::bail!("unexpected tile character `{c}`"),
         }
     }
 
     Ok(load)
 }
 
-fn tilt_north(platform: &mut [Vec<char>]) -> anyhow::Result<()> {
-    for j in 0..platform[0].len() {
+/// Rolls every round rock north. Any tile other than `O` and `#` counts as empty, as parsing has
+/// already rejected them.
+fn tilt_north(platform: &mut Grid<char>) {
+    for j in 0..platform.width() as isize {
         let mut empty_spot = 0;
-        for i in 0..platform.len() {
-            match platform[i][j] {
+        for i in 0..platform.height() as isize {
+            match platform[(i, j)] {
                 '#' => {
                     empty_spot = i + 1;
                 }
 
                 'O' => {
-                    let tmp = platform[empty_spot][j];
-                    platform[empty_spot][j] = platform[i][j];
-                    platform[i][j] = tmp;
+                    platform[(i, j)] = platform[(empty_spot, j)];
+                    platform[(empty_spot, j)] = 'O';
                     empty_spot += 1;
                 }
 
-                '.' => {}
-
-                c => anyhow::bail!("unexpected tile character `{c}`"),
-            }
-        }
-    }
-
-    Ok(())
-}
-
-fn tilt_east(platform: &mut [Vec<char>]) -> anyhow::Result<()> {
-    for i in 0..platform.len() {
-        let mut empty_spot = platform[i].len() - 1;
-        for j in (0..platform[i].len()).rev() {
-            match platform[i][j] {
-                '#' => {
-                    empty_spot = j.saturating_sub(1);
-                }
-
-                'O' => {
-                    platform[i].swap(empty_spot, j);
-                    empty_spot = empty_spot.saturating_sub(1);
-                }
-
-                '.' => {}
-
-                c => anyhow::bail!("unexpected tile character `{c}`"),
+                _ => {}
             }
         }
     }
-
-    Ok(())
 }
 
-fn tilt_south(platform: &mut [Vec<char>]) -> anyhow::Result<()> {
-    for j in 0..platform[0].len() {
-        let mut empty_spot = platform.len() - 1;
-        for i in (0..platform.len()).rev() {
-            match platform[i][j] {
-                '#' => {
-                    empty_spot = i.saturating_sub(1);
-                }
-
-                'O' => {
-                    let tmp = platform[empty_spot][j];
-                    platform[empty_spot][j] = platform[i][j];
-                    platform[i][j] = tmp;
-                    empty_spot = empty_spot.saturating_sub(1);
-                }
-
-                '.' => {}
-
-                c => anyhow::bail!("unexpected tile character `{c}`"),
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn example() {
+        let input = Day14::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day14::part_1(&input).unwrap().to_string(), "136");
+        assert_eq!(Day14::part_2(&input).unwrap().to_string(), "64");
     }
-
-    Ok(())
-}
-
-fn tilt_west(platform: &mut [Vec<char>]) -> anyhow::Result<()> {
-    for i in 0..platform.len() {
-        let mut empty_spot = 0;
-        for j in 0..platform[i].len() {
-            match platform[i][j] {
-                '#' => {
-                    empty_spot = j + 1;
-                }
-
-                'O' => {
-                    platform[i].swap(empty_spot, j);
-                    empty_spot += 1;
-                }
-
-                '.' => {}
-
-                c => anyhow::bail!("unexpected tile character `{c}`"),
-            }
-        }
-    }
-
-    Ok(())
 }
@@ -1,48 +1,107 @@
+use std::str::FromStr;
+
 use indexmap::IndexMap;
 
-pub fn solve() -> anyhow::Result<()> {
-    let mut part_1 = 0;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-    let mut boxes: Vec<IndexMap<String, usize>> = vec![IndexMap::new(); 256];
+pub struct Day15;
 
-    for step in std::fs::read_to_string("res/day15.txt")?.split(',') {
-        part_1 += hash(step);
+impl Solver for Day15 {
+    type Input = Vec<Step>;
 
-        let label = step
-            .chars()
-            .take_while(|c| c.is_alphabetic())
-            .collect::<String>();
-        let box_nr = hash(&label);
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let line = input.trim_end();
 
-        if step.ends_with('-') {
-            boxes[box_nr].shift_remove(&label);
-        } else if let Some(lens) = step.split('=').nth(1) {
-            let lens = lens.parse()?;
-            boxes[box_nr].insert(label, lens);
-        } else {
-            anyhow::bail!("unexpected step `{step}`");
+        Ok(line
+            .split(',')
+            .map(|step| {
+                step.parse()
+                    .map_err(|err: ParseError| err.within(line, step).at_line(1, line))
+            })
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(input.iter().map(|step| hash(&step.text)).sum::<usize>())
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut boxes: Vec<IndexMap<String, usize>> = vec![IndexMap::new(); 256];
+
+        for step in input.iter() {
+            let box_nr = hash(&step.label);
+
+            match step.operation {
+                Operation::Remove => {
+                    boxes[box_nr].shift_remove(&step.label);
+                }
+                Operation::Insert(lens) => {
+                    boxes[box_nr].insert(step.label.clone(), lens);
+                }
+            }
         }
 
-        // println!("{step:6} {:?}", &boxes[0..4]);
+        // One plus the box number of the lens in question.
+        // The slot number of the lens within the box: 1 for the first lens, 2 for the second lens, and so on.
+        // The focal length of the lens.
+        let part_2 = boxes
+            .iter()
+            .enumerate()
+            .map(|(box_nr, lenses)| {
+                lenses
+                    .values()
+                    .enumerate()
+                    .map(|(lens, &strength)| (box_nr + 1) * (lens + 1) * strength)
+                    .sum::<usize>()
+            })
+            .sum::<usize>();
+
+        Ok(part_2)
     }
+}
 
-    // One plus the box number of the lens in question.
-    // The slot number of the lens within the box: 1 for the first lens, 2 for the second lens, and so on.
-    // The focal length of the lens.
-    let part_2 = boxes
-        .iter()
-        .enumerate()
-        .map(|(box_nr, lenses)| {
-            lenses
-                .values()
-                .enumerate()
-                .map(|(lens, &strength)| (box_nr + 1) * (lens + 1) * strength)
-                .sum::<usize>()
-        })
-        .sum::<usize>();
+pub struct Step {
+    /// The step as written, which part 1 hashes
+    text: String,
+    label: String,
+    operation: Operation,
+}
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+enum Operation {
+    Remove,
+    /// Puts a lens of the given focal length in the box
+    Insert(usize),
+}
+
+impl FromStr for Step {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label_len = s
+            .find(|c: char| !c.is_alphabetic())
+            .ok_or_else(|| ParseError::end(s, "expected `-` or `=<focal length>`"))?;
+        let (label, operation) = s.split_at(label_len);
+
+        let operation = if operation == "-" {
+            Operation::Remove
+        } else if let Some(lens) = operation.strip_prefix('=') {
+            Operation::Insert(parse::number(lens).map_err(|err| err.within(s, lens))?)
+        } else {
+            return Err(ParseError::at(
+                s,
+                label_len,
+                "expected `-` or `=<focal length>`",
+            ));
+        };
+
+        Ok(Self {
+            text: s.to_string(),
+            label: label.to_string(),
+            operation,
+        })
+    }
 }
 
 fn hash(string: &str) -> usize {
@@ -56,3 +115,28 @@ fn hash(string: &str) -> usize {
 
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn example() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day15::part_1(&input).unwrap().to_string(), "1320");
+        assert_eq!(Day15::part_2(&input).unwrap().to_string(), "145");
+    }
+
+    #[test]
+    fn bad_focal_length() {
+        let err = Day15::parse("rn=1,cm=x2").err().unwrap();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!((err.line, err.column), (Some(1), 8));
+        assert_eq!(err.text, "rn=1,cm=x2");
+    }
+}
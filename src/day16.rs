@@ -1,62 +1,67 @@
-use std::collections::HashMap;
 use std::collections::HashSet;
 
+use palette::named;
+
 use crate::manhattan::step;
 use crate::manhattan::Direction;
-
-pub fn solve() -> anyhow::Result<()> {
-    let mut tiles: HashMap<(isize, isize), Tile> = HashMap::new();
-    let mut n = 0;
-    let mut m = 0;
-
-    for (i, line) in std::fs::read_to_string("res/day16.txt")?
-        .lines()
-        .enumerate()
-    {
-        for (j, c) in line.chars().enumerate() {
-            if c == '.' {
-                continue;
-            }
-
-            let tile = c.try_into()?;
-
-            tiles.insert((i as isize, j as isize), tile);
-            n = n.max(i + 1);
-            m = n.max(j + 1);
-        }
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    /// Tile at every position, if it isn't empty space
+    type Input = Grid<Option<Tile>>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let contraption = Grid::parse_with(input, |c| match c {
+            '.' => Ok(None),
+            c => Tile::try_from(c).map(Some),
+        })?;
+
+        Ok(contraption)
     }
 
-    let n = n as isize;
-    let m = m as isize;
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let energized = beam((0, 0), Direction::East, input);
+        render::show("Energized tiles:", || draw(input, &energized));
 
-    let part_1 = beam(0, 0, Direction::East, n, m, &tiles);
+        Ok(energized.len())
+    }
 
-    let part_2 = (0..n)
-        .flat_map(|i| {
-            [
-                beam(i, 0, Direction::East, n, m, &tiles),
-                beam(i, m - 1, Direction::West, n, m, &tiles),
-            ]
-        })
-        .max()
-        .max(
-            (0..m)
-                .flat_map(|j| {
-                    [
-                        beam(0, j, Direction::South, n, m, &tiles),
-                        beam(n - 1, j, Direction::North, n, m, &tiles),
-                    ]
-                })
-                .max(),
-        )
-        .unwrap_or_default();
-
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-
-    Ok(())
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let (n, m) = (input.height() as isize, input.width() as isize);
+
+        let part_2 = (0..n)
+            .flat_map(|i| {
+                [
+                    beam((i, 0), Direction::East, input).len(),
+                    beam((i, m - 1), Direction::West, input).len(),
+                ]
+            })
+            .max()
+            .max(
+                (0..m)
+                    .flat_map(|j| {
+                        [
+                            beam((0, j), Direction::South, input).len(),
+                            beam((n - 1, j), Direction::North, input).len(),
+                        ]
+                    })
+                    .max(),
+            )
+            .unwrap_or_default();
+
+        Ok(part_2)
+    }
 }
 
-enum Tile {
+pub enum Tile {
     /// `/`
     MirrorForward,
     /// `\`
@@ -68,7 +73,7 @@ enum Tile {
 }
 
 impl TryFrom<char> for Tile {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         Ok(match value {
@@ -76,11 +81,22 @@ impl TryFrom<char> for Tile {
             '\\' => Self::MirrorBackward,
             '|' => Self::SplitterVertical,
             '-' => Self::SplitterHorizontal,
-            c => anyhow::bail!("unexpected tile '{c}'"),
+            c => return Err(ParseError::char(c, format!("unexpected tile '{c}'"))),
         })
     }
 }
 
+impl From<&Tile> for char {
+    fn from(tile: &Tile) -> Self {
+        match tile {
+            Tile::MirrorForward => '/',
+            Tile::MirrorBackward => '\\',
+            Tile::SplitterVertical => '|',
+            Tile::SplitterHorizontal => '-',
+        }
+    }
+}
+
 impl Tile {
     fn next_dir(&self, dir: Direction) -> &[Direction] {
         match (self, dir) {
@@ -104,51 +120,79 @@ impl Tile {
     }
 }
 
-fn beam(
-    i: isize,
-    j: isize,
-    dir: Direction,
-    n: isize,
-    m: isize,
-    tiles: &HashMap<(isize, isize), Tile>,
-) -> usize {
-    let mut beams = vec![(i, j, dir)];
+/// Tiles energized by a beam entering the contraption at `start`, heading `dir`
+fn beam(start: Position, dir: Direction, contraption: &Grid<Option<Tile>>) -> HashSet<Position> {
+    let mut beams = vec![(start, dir)];
     let mut hist = HashSet::new();
 
-    while let Some(beam) = beams.pop() {
-        hist.insert(beam);
-
-        match tiles.get(&(beam.0, beam.1)) {
-            Some(tile) => {
-                for dir in tile.next_dir(beam.2) {
-                    let next = step((beam.0, beam.1), *dir);
-
-                    if next.0 >= 0
-                        && next.0 < n
-                        && next.1 >= 0
-                        && next.1 < m
-                        && !hist.contains(&(next.0, next.1, *dir))
-                    {
-                        beams.push((next.0, next.1, *dir));
-                    }
-                }
-            }
-            None => {
-                let next = step((beam.0, beam.1), beam.2);
-                if next.0 >= 0
-                    && next.0 < n
-                    && next.1 >= 0
-                    && next.1 < m
-                    && !hist.contains(&(next.0, next.1, beam.2))
-                {
-                    beams.push((next.0, next.1, beam.2));
-                }
+    while let Some((pos, dir)) = beams.pop() {
+        hist.insert((pos, dir));
+
+        let next_dirs = match &contraption[pos] {
+            Some(tile) => tile.next_dir(dir),
+            None => std::slice::from_ref(&dir),
+        };
+
+        for dir in next_dirs {
+            let next = step(pos, *dir);
+
+            if contraption.contains(next) && !hist.contains(&(next, *dir)) {
+                beams.push((next, *dir));
             }
         }
     }
 
-    hist.iter()
-        .map(|(i, j, _)| (*i, *j))
-        .collect::<HashSet<_>>()
-        .len()
+    hist.iter().map(|(pos, _)| *pos).collect()
+}
+
+/// Draws the contraption, marking energized empty tiles with `#` and every energized tile with a
+/// background colour
+fn draw(contraption: &Grid<Option<Tile>>, energized: &HashSet<Position>) -> Picture {
+    let glow = named::DARKORANGE;
+
+    Picture::new(contraption, |pos, tile| {
+        match (tile, energized.contains(&pos)) {
+            (Some(tile), true) => Cell::new(tile.into()).fg(named::LIGHTSTEELBLUE).bg(glow),
+            (Some(tile), false) => Cell::new(tile.into()).fg(named::LIGHTSTEELBLUE),
+            (None, true) => Cell::new('#').fg(named::YELLOW).bg(glow),
+            (None, false) => Cell::new('·').fg(named::DIMGRAY),
+        }
+    })
+    .legend(Cell::new('#').fg(named::YELLOW).bg(glow), "energized")
+    .legend(
+        Cell::new('/').fg(named::LIGHTSTEELBLUE),
+        "mirror or splitter",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn example() {
+        let input = Day16::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day16::part_1(&input).unwrap().to_string(), "46");
+        assert_eq!(Day16::part_2(&input).unwrap().to_string(), "51");
+    }
+
+    #[test]
+    fn wider_than_tall() {
+        let input = Day16::parse(".....\n.....").unwrap();
+
+        assert_eq!(Day16::part_1(&input).unwrap().to_string(), "5");
+        assert_eq!(Day16::part_2(&input).unwrap().to_string(), "5");
+    }
 }
@@ -1,99 +1,182 @@
 use anyhow::Context;
-use std::collections::HashMap;
-use std::collections::HashSet;
+use palette::named;
 use std::collections::VecDeque;
 
 use crate::manhattan::a_star;
 use crate::manhattan::neighbours;
 use crate::manhattan::Direction;
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+
+pub struct Day17;
+
+impl Solver for Day17 {
+    /// Heat loss of every city block
+    type Input = Grid<usize>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let grid = Grid::parse_with(input, |c| {
+            c.to_digit(10)
+                .map(|loss| loss as usize)
+                .ok_or_else(|| ParseError::char(c, format!("invalid digit `{c}`")))
+        })?;
+
+        Ok(grid)
+    }
 
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day17.txt")?;
-    let mut grid = HashMap::new();
-    let mut n = 0;
-    let mut m = 0;
-
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            let loss = c.to_digit(10).context("invalid digit")?;
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let (n, m) = bottom_right(input);
 
-            grid.insert((i as isize, j as isize), loss as usize);
-        }
-        n = i as isize;
-        m = m.max((line.len() - 1) as isize);
-    }
+        let done = |curr: &VecDeque<(isize, isize)>| curr.front() == Some(&(n, m));
+        let nexts = |curr: &VecDeque<(isize, isize)>| {
+            let head = curr.front().unwrap();
 
-    let done = |curr: &VecDeque<(isize, isize)>| curr.front() == Some(&(n, m));
-    let nexts = |curr: &VecDeque<(isize, isize)>| {
-        let head = curr.front().unwrap();
+            let is_row = curr.len() == 4 && curr.iter().all(|p| p.0 == head.0);
+            let is_col = curr.len() == 4 && curr.iter().all(|p| p.1 == head.1);
 
-        let is_row = curr.len() == 4 && curr.iter().all(|p| p.0 == head.0);
-        let is_col = curr.len() == 4 && curr.iter().all(|p| p.1 == head.1);
+            let mut ret = Vec::with_capacity(4);
 
-        let mut ret = HashSet::with_capacity(4);
+            for (neighbour, _dir) in neighbours(*head) {
+                let Some(loss) = input.get(neighbour) else {
+                    continue;
+                };
 
-        for (neighbour, _dir) in neighbours(*head) {
-            let Some(loss) = grid.get(&neighbour) else {
-                continue;
-            };
+                if (is_row && neighbour.0 == head.0)
+                    || (is_col && neighbour.1 == head.1)
+                    || curr.contains(&neighbour)
+                {
+                    continue;
+                }
 
-            if (is_row && neighbour.0 == head.0)
-                || (is_col && neighbour.1 == head.1)
-                || curr.contains(&neighbour)
-            {
-                continue;
+                let mut next = curr.clone();
+                next.push_front(neighbour);
+                next.truncate(4);
+                ret.push((next, *loss));
             }
 
-            let mut next = curr.clone();
-            next.push_front(neighbour);
-            next.truncate(4);
-            ret.insert((next, *loss));
-        }
-
-        ret
-    };
-    let heur = |curr: &VecDeque<(isize, isize)>| {
-        let head = curr.front().unwrap();
-        (n - head.0).unsigned_abs() + (m - head.1).unsigned_abs()
-    };
-
-    let debug = |_: &_, _: &_, _: &_| {};
-
-    let (_, part_1) =
-        a_star(VecDeque::from([(0, 0)]), done, nexts, heur, debug).context("no path found")?;
-
-    let finished = |curr: &(isize, isize, Direction)| curr.0 == n && curr.1 == m;
-    let nexts = |curr: &(isize, isize, Direction)| {
-        let mut nexts = HashSet::new();
-
-        for step_len in 4..=10 {
-            'dir: for dir in [curr.2.turn_left(), curr.2.turn_right()] {
-                let step = dir.forward_step();
-                let mut next = (curr.0, curr.1);
-                let mut total_loss = 0;
-                for _ in 0..step_len {
-                    next.0 += step.0;
-                    next.1 += step.1;
-                    let Some(loss) = grid.get(&next) else {
-                        continue 'dir;
-                    };
-                    total_loss += *loss;
+            ret
+        };
+        let heur = |curr: &VecDeque<(isize, isize)>| {
+            let head = curr.front().unwrap();
+            (n - head.0).unsigned_abs() + (m - head.1).unsigned_abs()
+        };
+
+        let (found, _) = a_star(VecDeque::from([(0, 0)]), done, nexts, heur, None);
+        let (path, part_1) = found.context("no path found")?;
+
+        render::show("Least heat loss path:", || {
+            draw(input, path.iter().filter_map(|blocks| blocks.front()))
+        });
+
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let (n, m) = bottom_right(input);
+
+        let finished = |curr: &(isize, isize, Direction)| curr.0 == n && curr.1 == m;
+        let nexts = |curr: &(isize, isize, Direction)| {
+            let mut nexts = Vec::new();
+
+            for step_len in 4..=10 {
+                'dir: for dir in [curr.2.turn_left(), curr.2.turn_right()] {
+                    let step = dir.forward_step();
+                    let mut next = (curr.0, curr.1);
+                    let mut total_loss = 0;
+                    for _ in 0..step_len {
+                        next.0 += step.0;
+                        next.1 += step.1;
+                        let Some(loss) = input.get(next) else {
+                            continue 'dir;
+                        };
+                        total_loss += *loss;
+                    }
+                    nexts.push(((next.0, next.1, dir), total_loss));
                 }
-                nexts.insert(((next.0, next.1, dir), total_loss));
             }
-        }
 
-        nexts
-    };
-    let heur = |curr: &(isize, isize, Direction)| {
-        (n - curr.0).unsigned_abs() + (m - curr.1).unsigned_abs()
-    };
-    let debug = |_: &_, _: &_, _: &_| {};
+            nexts
+        };
+        let heur = |curr: &(isize, isize, Direction)| {
+            (n - curr.0).unsigned_abs() + (m - curr.1).unsigned_abs()
+        };
+
+        // Facing north or west initially lets the crucible start moving east or south respectively
+        let part_2 = [Direction::North, Direction::West]
+            .into_iter()
+            .filter_map(|dir| a_star((0, 0, dir), finished, nexts, heur, None).0)
+            .map(|(_, loss)| loss)
+            .min()
+            .context("no path found")?;
+
+        Ok(part_2)
+    }
+}
+
+/// Draws the heat loss of every block, from blue to red, highlighting the blocks on `path`
+fn draw<'a>(grid: &Grid<usize>, path: impl Iterator<Item = &'a Position>) -> Picture {
+    let heat = |loss: usize| Cell::new(char::from_digit(loss as u32, 10).unwrap_or('?'));
+    let shade = |loss: usize| render::gradient((loss as f32 - 1.0) / 8.0);
 
-    // initial direction is input-specific
-    let (_, part_2) =
-        a_star((0, 0, Direction::North), finished, nexts, heur, debug).context("no path found")?;
+    let mut picture = Picture::new(grid, |_, loss| heat(*loss).fg(shade(*loss)))
+        .legend(heat(1).fg(shade(1)), "least heat loss")
+        .legend(heat(9).fg(shade(9)), "most heat loss")
+        .legend(Cell::new(' ').bg(named::WHITE), "crucible path");
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+    for pos in path {
+        picture.set(*pos, heat(grid[*pos]).fg(named::BLACK).bg(named::WHITE));
+    }
+
+    picture
+}
+
+fn bottom_right(grid: &Grid<usize>) -> Position {
+    (grid.height() as isize - 1, grid.width() as isize - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    #[test]
+    fn example() {
+        let input = Day17::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day17::part_1(&input).unwrap().to_string(), "102");
+        assert_eq!(Day17::part_2(&input).unwrap().to_string(), "94");
+    }
+
+    #[test]
+    fn example_ultra_crucible() {
+        const EXAMPLE: &str = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+
+        let input = Day17::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day17::part_2(&input).unwrap().to_string(), "71");
+    }
 }
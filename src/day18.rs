@@ -1,55 +1,168 @@
+use palette::named;
+
+use crate::manhattan::polygon::contains_point;
+use crate::manhattan::polygon::on_boundary;
+use crate::manhattan::polygon::Overflow;
 use crate::manhattan::polygon_area;
 use crate::manhattan::step_many;
 use crate::manhattan::Direction;
-use anyhow::Context;
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+
+pub struct Day18;
 
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day18.txt")?;
+/// Direction to dig in, and how far
+pub type Instruction = (Direction, usize);
 
-    let mut points_1 = vec![];
-    let mut curr_1 = (0, 0);
-    let mut points_2 = vec![];
-    let mut curr_2 = (0, 0);
+impl Solver for Day18 {
+    /// Dig plan, read from the instructions and from the colour codes respectively
+    type Input = (Vec<Instruction>, Vec<Instruction>);
 
-    for line in input.lines() {
-        let mut split = line.split_whitespace();
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let (plan_1, plan_2) = parse::lines(input, parse_line)?.into_iter().unzip();
 
-        let dir_1 = match split.next().context("no direction found")? {
-            "U" => Direction::North,
-            "R" => Direction::East,
-            "D" => Direction::South,
-            "L" => Direction::West,
-            s => anyhow::bail!("unexpected direction '{s}' found"),
-        };
+        Ok((plan_1, plan_2))
+    }
 
-        let steps_1 = split.next().context("no steps found")?.parse::<usize>()?;
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        render::show("Lagoon:", || draw(&trench_corners(&input.0)));
 
-        let hex = split
-            .next()
-            .context("no color found")?
-            .strip_prefix("(#")
-            .and_then(|s| s.strip_suffix(')'))
-            .context("unexpected color pattern")?;
-
-        let steps_2 = usize::from_str_radix(&hex[0..5], 16)?;
-        let dir_2 = match &hex[5..] {
-            "0" => Direction::East,
-            "1" => Direction::South,
-            "2" => Direction::West,
-            "3" => Direction::North,
-            s => anyhow::bail!("unexpected hex direction '{s}'"),
-        };
-
-        curr_2 = step_many(curr_2, dir_2, steps_2);
-        points_2.push(curr_2);
-
-        curr_1 = step_many(curr_1, dir_1, steps_1);
-        points_1.push(curr_1);
+        Ok(lagoon_area(&input.0)?)
     }
 
-    let part_1 = polygon_area(points_1.into_iter());
-    let part_2 = polygon_area(points_2.into_iter());
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(lagoon_area(&input.1)?)
+    }
+}
+
+fn lagoon_area(plan: &[Instruction]) -> Result<u128, Overflow> {
+    polygon_area(&trench_corners(plan))
+}
+
+/// Positions at which the trench turns, starting and ending where digging started
+fn trench_corners(plan: &[Instruction]) -> Vec<Position> {
+    let mut curr = (0, 0);
+
+    plan.iter()
+        .map(|(dir, steps)| {
+            curr = step_many(curr, *dir, *steps);
+            curr
+        })
+        .collect()
+}
+
+/// Draws the trench and the lagoon it encloses, within their bounding box
+fn draw(corners: &[Position]) -> Picture {
+    let top_left = corners
+        .iter()
+        .fold((0, 0), |a, b| (a.0.min(b.0), a.1.min(b.1)));
+    let bottom_right = corners
+        .iter()
+        .fold((0, 0), |a, b| (a.0.max(b.0), a.1.max(b.1)));
+    let size = (
+        (bottom_right.0 - top_left.0 + 1) as usize,
+        (bottom_right.1 - top_left.1 + 1) as usize,
+    );
+
+    let trench = Cell::new('#').fg(named::SADDLEBROWN);
+    let lagoon = Cell::new('~').fg(named::DODGERBLUE);
+    let ground = Cell::new('·').fg(named::DIMGRAY);
+
+    let site = Grid::from_fn(size.0, size.1, |(i, j)| (i + top_left.0, j + top_left.1));
+    // Anything small enough to draw is far from overflowing
+    Picture::new(&site, |_, pos| {
+        if on_boundary(corners, *pos) == Ok(true) {
+            trench
+        } else if contains_point(corners, *pos) == Ok(true) {
+            lagoon
+        } else {
+            ground
+        }
+    })
+    .legend(trench, "trench")
+    .legend(lagoon, "lagoon")
+}
+
+/// Parses a dig plan instruction, both as written and as encoded in its colour code
+fn parse_line(line: &str) -> Result<(Instruction, Instruction), ParseError> {
+    let mut split = line.split_whitespace();
+    let mut next = |what| {
+        split
+            .next()
+            .ok_or_else(|| ParseError::end(line, format!("no {what} found")))
+    };
+
+    let (dir, steps, color) = (next("direction")?, next("steps")?, next("color")?);
+
+    let dir_1 = match dir {
+        "U" => Direction::North,
+        "R" => Direction::East,
+        "D" => Direction::South,
+        "L" => Direction::West,
+        s => {
+            return Err(
+                ParseError::new(s, format!("unexpected direction '{s}' found")).within(line, s),
+            )
+        }
+    };
+
+    let steps_1 = parse::number(steps).map_err(|err| err.within(line, steps))?;
+
+    let hex = color
+        .strip_prefix("(#")
+        .and_then(|s| s.strip_suffix(')'))
+        .filter(|hex| hex.len() == 6)
+        .ok_or_else(|| ParseError::new(color, "unexpected color pattern").within(line, color))?;
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+    let steps_2 = usize::from_str_radix(&hex[0..5], 16).map_err(|err| {
+        ParseError::new(&hex[0..5], format!("invalid hex distance: {err}")).within(line, hex)
+    })?;
+    let dir_2 = match &hex[5..] {
+        "0" => Direction::East,
+        "1" => Direction::South,
+        "2" => Direction::West,
+        "3" => Direction::North,
+        s => {
+            return Err(
+                ParseError::at(hex, 5, format!("unexpected hex direction '{s}'")).within(line, hex),
+            )
+        }
+    };
+
+    Ok(((dir_1, steps_1), (dir_2, steps_2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn example() {
+        let input = Day18::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day18::part_1(&input).unwrap().to_string(), "62");
+        assert_eq!(Day18::part_2(&input).unwrap().to_string(), "952408144115");
+    }
 }
@@ -3,53 +3,94 @@ use std::str::FromStr;
 
 use anyhow::Context;
 
-#[allow(clippy::while_let_on_iterator)]
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day19.txt")?;
-    let mut lines = input.lines();
-
-    let mut workflows = HashMap::new();
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-
-        let (name, rules_str) = sscanf::scanf!(line, "{}{{{}}}", String, &str)
-            .map_err(|e| anyhow::Error::msg(e.to_string()))?;
-
-        let rules = rules_str
-            .split(',')
-            .map(|r| r.parse())
-            .collect::<Result<_, _>>()?;
-
-        workflows.insert(name.clone(), Workflow { rules });
-    }
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = System;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let blocks = parse::split_blocks(input);
+        let [(workflows_start, workflows_str), (parts_start, parts_str)] = blocks[..] else {
+            anyhow::bail!("expected workflows and parts, separated by a blank line");
+        };
+
+        let workflows = parse::lines(workflows_str, parse_workflow)
+            .map_err(|err| err.below(workflows_start))?
+            .into_iter()
+            .collect();
+
+        let parts = parse::lines(parts_str, |line| {
+            // parts may also be given as JSON objects, e.g. `{"x":787,"m":2655,"a":1222,"s":2876}`
+            match serde_json::from_str::<HashMap<char, usize>>(line) {
+                Ok(part) => Ok(part),
+                Err(_) => parse_part(line),
+            }
+        })
+        .map_err(|err| err.below(parts_start))?;
 
-    let mut parts = vec![];
-    for line in lines {
-        let part = serde_json::from_str::<HashMap<char, usize>>(line)?;
-        parts.push(part);
+        Ok(System { workflows, parts })
     }
 
-    let mut part_1 = 0;
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_1 = 0;
 
-    for part in parts {
-        if process_part(&part, &workflows)? {
-            part_1 += part.values().sum::<usize>()
+        for part in input.parts.iter() {
+            if process_part(part, &input.workflows)? {
+                part_1 += part.values().sum::<usize>()
+            }
         }
+
+        Ok(part_1)
     }
 
-    let mut part_2 = 0;
-    for (name, workflow) in workflows.iter() {
-        for (idx, rule) in workflow.rules.iter().enumerate() {
-            if matches!(rule.outcome, Outcome::Accept) {
-                part_2 += reverse(name, idx, &workflows)?;
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_2 = 0;
+        for (name, workflow) in input.workflows.iter() {
+            for (idx, rule) in workflow.rules.iter().enumerate() {
+                if matches!(rule.outcome, Outcome::Accept) {
+                    part_2 += reverse(name, idx, &input.workflows)?;
+                }
             }
         }
+
+        Ok(part_2)
     }
+}
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+pub struct System {
+    workflows: HashMap<String, Workflow>,
+    parts: Vec<HashMap<char, usize>>,
+}
+
+/// Parses a workflow, e.g. `px{a<2006:qkq,m>2090:A,rfg}`
+fn parse_workflow(line: &str) -> Result<(String, Workflow), ParseError> {
+    let (name, rules_str) = sscanf::scanf!(line, "{}{{{}}}", String, &str)
+        .map_err(|_| ParseError::new(line, "expected `<name>{<rules>}`"))?;
+
+    let rules = rules_str
+        .split(',')
+        .map(|r| r.parse().map_err(|err: ParseError| err.within(line, r)))
+        .collect::<Result<_, _>>()?;
+
+    Ok((name, Workflow { rules }))
+}
+
+/// Parses a part's ratings, e.g. `{x=787,m=2655,a=1222,s=2876}`
+fn parse_part(line: &str) -> Result<HashMap<char, usize>, ParseError> {
+    line.strip_prefix('{')
+        .and_then(|l| l.strip_suffix('}'))
+        .ok_or_else(|| ParseError::new(line, "unexpected part format"))?
+        .split(',')
+        .map(|rating| {
+            sscanf::scanf!(rating, "{}={}", char, usize).map_err(|_| {
+                ParseError::new(rating, "expected `<category>=<rating>`").within(line, rating)
+            })
+        })
+        .collect()
 }
 
 fn reverse<'a, 'b: 'a>(
@@ -180,7 +221,7 @@ enum Outcome {
 }
 
 impl FromStr for Outcome {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
@@ -192,18 +233,24 @@ impl FromStr for Outcome {
 }
 
 impl FromStr for Rule {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.contains(':') {
             let (cat, cmp_char, val, outcome_str) =
                 sscanf::scanf!(s, "{}{}{}:{}", char, char, usize, &str)
-                    .map_err(|e| anyhow::Error::msg(e.to_string()))?;
+                    .map_err(|_| ParseError::new(s, "expected `<category><op><value>:<goto>`"))?;
 
             let cmp = match cmp_char {
                 '<' => std::cmp::Ordering::Less,
                 '>' => std::cmp::Ordering::Greater,
-                c => anyhow::bail!("unexpected comparator char '{c}'"),
+                c => {
+                    return Err(ParseError::at(
+                        s,
+                        1,
+                        format!("unexpected comparator char '{c}'"),
+                    ))
+                }
             };
 
             let outcome = Outcome::from_str(outcome_str)?;
@@ -220,3 +267,38 @@ impl FromStr for Rule {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2005,s=1013}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn example() {
+        let input = Day19::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day19::part_1(&input).unwrap().to_string(), "19114");
+        assert_eq!(
+            Day19::part_2(&input).unwrap().to_string(),
+            "167409079868000"
+        );
+    }
+}
@@ -1,62 +1,69 @@
-use anyhow::Context;
 use std::collections::HashMap;
 use std::str::FromStr;
 
-pub fn solve() -> anyhow::Result<()> {
-    let text = std::fs::read_to_string("res/day02.txt")?;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-    let mut part_1 = 0;
-    let mut part_2 = 0;
+pub struct Day2;
 
-    for (line_id, line) in text.lines().enumerate() {
-        let game = parse_line(line)?;
+impl Solver for Day2 {
+    type Input = Vec<Vec<HashMap<Color, u32>>>;
 
-        let possible = game.iter().all(|set| {
-            set.get(&Color::Red).cloned().unwrap_or_default() <= 12
-                && set.get(&Color::Green).cloned().unwrap_or_default() <= 13
-                && set.get(&Color::Blue).cloned().unwrap_or_default() <= 14
-        });
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse::lines(input, parse_line)?)
+    }
 
-        if possible {
-            part_1 += line_id + 1;
-        }
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_1 = 0;
+
+        for (line_id, game) in input.iter().enumerate() {
+            let possible = game.iter().all(|set| {
+                set.get(&Color::Red).cloned().unwrap_or_default() <= 12
+                    && set.get(&Color::Green).cloned().unwrap_or_default() <= 13
+                    && set.get(&Color::Blue).cloned().unwrap_or_default() <= 14
+            });
 
-        let mut max = HashMap::new();
-        for (color, num) in game.into_iter().flat_map(|set| set.into_iter()) {
-            let curr = max.entry(color).or_insert(num);
-            *curr = std::cmp::max(*curr, num);
+            if possible {
+                part_1 += line_id + 1;
+            }
         }
-        part_2 += max.into_values().product::<u32>();
+
+        Ok(part_1)
     }
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_2 = 0;
 
-    Ok(())
+        for game in input.iter() {
+            let mut max = HashMap::new();
+            for (color, num) in game.iter().flat_map(|set| set.iter()) {
+                let curr = max.entry(*color).or_insert(*num);
+                *curr = std::cmp::max(*curr, *num);
+            }
+            part_2 += max.into_values().product::<u32>();
+        }
+
+        Ok(part_2)
+    }
 }
 
-fn parse_line(line: &str) -> anyhow::Result<Vec<HashMap<Color, u32>>> {
+fn parse_line(line: &str) -> Result<Vec<HashMap<Color, u32>>, ParseError> {
     // Game 1: 9 red, 5 blue, 6 green; 6 red, 13 blue; 2 blue, 7 green, 5 red
-    let sets = line
-        .split(": ")
-        .nth(1)
-        .context("no title found")?
-        .split("; ");
+    let (_, sets) = parse::key_value(line, ":")?;
     let mut parsed_sets = vec![];
 
-    for set in sets {
+    for set in sets.split("; ") {
         let mut parsed_set = HashMap::default();
         for cubes in set.split(", ") {
-            let mut parts = cubes.split(' ');
-
-            let cnt = parts
-                .next()
-                .and_then(|s| s.parse().ok())
-                .context("number of cubes not found")?;
-            let color = parts
-                .next()
-                .and_then(|s| s.parse().ok())
-                .context("number of cubes not found")?;
-
+            let (cnt, color) = cubes.split_once(' ').ok_or_else(|| {
+                ParseError::new(cubes, "expected a number of cubes and a color").within(line, cubes)
+            })?;
+
+            let color = color
+                .parse()
+                .map_err(|err: ParseError| err.within(line, color))?;
+            let cnt = parse::number(cnt).map_err(|err| err.within(line, cnt))?;
             parsed_set.insert(color, cnt);
         }
         parsed_sets.push(parsed_set);
@@ -66,21 +73,50 @@ fn parse_line(line: &str) -> anyhow::Result<Vec<HashMap<Color, u32>>> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Color {
+pub enum Color {
     Red,
     Green,
     Blue,
 }
 
 impl FromStr for Color {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s.to_lowercase().as_str() {
             "red" => Self::Red,
             "green" => Self::Green,
             "blue" => Self::Blue,
-            other => anyhow::bail!("unknown color `{other}`"),
+            other => return Err(ParseError::new(s, format!("unknown color `{other}`"))),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn example() {
+        let input = Day2::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day2::part_1(&input).unwrap().to_string(), "8");
+        assert_eq!(Day2::part_2(&input).unwrap().to_string(), "2286");
+    }
+
+    #[test]
+    fn errors_point_at_the_bad_cubes() {
+        let err = parse_line("Game 1: 3 blue, 2 e").unwrap_err();
+        assert_eq!(err.column, 18);
+
+        let err = parse_line("Game 1: 3 blue, x red").unwrap_err();
+        assert_eq!(err.column, 16);
+    }
+}
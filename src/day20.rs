@@ -1,4 +1,8 @@
-use crate::utils::lcm_many;
+use crate::diag;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use crate::utils::align_cycles;
 use anyhow::Context;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -7,84 +11,110 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::str::FromStr;
 
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day20.txt")?;
+pub struct Day20;
 
-    let mut modules = BTreeMap::new();
-    for line in input.lines() {
-        let module = Module::from_str(line)?;
-        modules.insert(module.id().clone(), module);
-    }
+impl Solver for Day20 {
+    type Input = BTreeMap<ModuleId, Module>;
 
-    // prime the conjuction modules
-    for id in modules.keys().cloned().collect::<Vec<_>>() {
-        let dests = modules.get(&id).context("wat")?.dest().to_vec();
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let mut modules = BTreeMap::new();
+        for module in parse::lines(input, Module::from_str)? {
+            modules.insert(module.id().clone(), module);
+        }
 
-        for dest in dests {
-            if dest == "output" {
-                continue;
-            }
+        // prime the conjuction modules
+        for id in modules.keys().cloned().collect::<Vec<_>>() {
+            let dests = modules.get(&id).context("wat")?.dest().to_vec();
 
-            if let Some(Module::Conjuction { values, .. }) = modules.get_mut(&dest) {
-                values.insert(id.clone(), PulseValue::Low);
+            for dest in dests {
+                if dest == "output" {
+                    continue;
+                }
+
+                if let Some(Module::Conjuction { values, .. }) = modules.get_mut(&dest) {
+                    values.insert(id.clone(), PulseValue::Low);
+                }
             }
         }
+
+        Ok(modules)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut modules = input.clone();
+        let mut low_pulses = 0;
+        let mut high_pulses = 0;
+
+        for _ in 0..1_000 {
+            press_button(&mut modules, |pulse| match pulse.value {
+                PulseValue::Low => low_pulses += 1,
+                PulseValue::High => high_pulses += 1,
+            });
+        }
+
+        Ok(low_pulses * high_pulses)
     }
 
-    let mut bus = VecDeque::new();
-    let mut low_pulses = 0;
-    let mut high_pulses = 0;
-
-    let mut nand_freqs = HashMap::from([
-        ("sk".to_string(), None),
-        ("sv".to_string(), None),
-        ("dr".to_string(), None),
-        ("qz".to_string(), None),
-    ]);
-
-    'outer: for i in 0.. {
-        // send button pulse
-        bus.push_back(Pulse {
-            from: "button".to_string(),
-            to: "broadcaster".to_string(),
-            value: PulseValue::Low,
-        });
-
-        // handle all subsequent pulses
-        while let Some(pulse) = bus.pop_front() {
-            if i < 1_000 {
-                match pulse.value {
-                    PulseValue::Low => low_pulses += 1,
-                    PulseValue::High => high_pulses += 1,
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut modules = input.clone();
+
+        // Presses after which each of these sent a low pulse, stopping at the second one, which
+        // gives the offset and period of their cycles
+        let mut nand_presses = HashMap::from([
+            ("sk".to_string(), Vec::with_capacity(2)),
+            ("sv".to_string(), Vec::with_capacity(2)),
+            ("dr".to_string(), Vec::with_capacity(2)),
+            ("qz".to_string(), Vec::with_capacity(2)),
+        ]);
+
+        for i in 0.. {
+            press_button(&mut modules, |pulse| {
+                if !matches!(pulse.value, PulseValue::Low) {
+                    return;
                 }
-            }
 
-            if matches!(pulse.value, PulseValue::Low) && nand_freqs.get(&pulse.from) == Some(&None)
-            {
-                println!("{} LOW after {} presses", pulse.from, i + 1);
-                nand_freqs.insert(pulse.from.clone(), Some(i + 1));
-                if nand_freqs.values().all(|nand| nand.is_some()) {
-                    break 'outer;
+                if let Some(presses) = nand_presses.get_mut(&pulse.from) {
+                    if presses.len() < 2 && presses.last() != Some(&(i + 1)) {
+                        diag!("{} LOW after {} presses", pulse.from, i + 1);
+                        presses.push(i + 1);
+                    }
                 }
-            }
+            });
 
-            if let Some(dest_module) = modules.get_mut(&pulse.to) {
-                dest_module.handle_pulse(pulse, &mut bus);
+            if nand_presses.values().all(|presses| presses.len() == 2) {
+                break;
             }
         }
+
+        let cycles = nand_presses
+            .values()
+            .map(|presses| (presses[0], presses[1] - presses[0]))
+            .collect::<Vec<_>>();
+
+        align_cycles(&cycles).context("the modules never all send a low pulse at once")
     }
+}
 
-    let part_1 = low_pulses * high_pulses;
+/// Sends a low pulse to the broadcaster and handles all subsequent pulses, passing each one to
+/// `on_pulse` before it's delivered
+fn press_button(modules: &mut BTreeMap<ModuleId, Module>, mut on_pulse: impl FnMut(&Pulse)) {
+    let mut bus = VecDeque::from([Pulse {
+        from: "button".to_string(),
+        to: "broadcaster".to_string(),
+        value: PulseValue::Low,
+    }]);
 
-    let freqs = nand_freqs.values().filter_map(|v| *v).collect::<Vec<_>>();
-    let part_2 = lcm_many(&freqs);
+    while let Some(pulse) = bus.pop_front() {
+        on_pulse(&pulse);
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+        if let Some(dest_module) = modules.get_mut(&pulse.to) {
+            dest_module.handle_pulse(pulse, &mut bus);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-enum Module {
+pub enum Module {
     FlipFlop {
         id: ModuleId,
         value: PulseValue,
@@ -171,13 +201,10 @@ impl Module {
 }
 
 impl FromStr for Module {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split(" -> ");
-
-        let module_str = split.next().context("missing module string")?;
-        let dest_str = split.next().context("missing destination string")?;
+        let (module_str, dest_str) = parse::key_value(s, "->")?;
 
         let dest = dest_str.split(", ").map(|s| s.to_string()).collect();
 
@@ -199,7 +226,10 @@ impl FromStr for Module {
                 dest,
             })
         } else {
-            anyhow::bail!("unexpected module string: '{s}'");
+            Err(ParseError::new(
+                s,
+                format!("unexpected module string: '{module_str}'"),
+            ))
         }
     }
 }
@@ -223,7 +253,7 @@ struct Pulse {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum PulseValue {
+pub enum PulseValue {
     Low,
     High,
 }
@@ -274,3 +304,30 @@ impl std::fmt::Display for PulseValue {
 }
 
 type ModuleId = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part_1() {
+        const EXAMPLE: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+        const INTERESTING: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+        let input = Day20::parse(EXAMPLE).unwrap();
+        assert_eq!(Day20::part_1(&input).unwrap().to_string(), "32000000");
+
+        let input = Day20::parse(INTERESTING).unwrap();
+        assert_eq!(Day20::part_1(&input).unwrap().to_string(), "11687500");
+    }
+}
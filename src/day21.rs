@@ -1,75 +1,111 @@
-use anyhow::Context;
-use std::collections::HashMap;
 use std::collections::HashSet;
 
-use crate::manhattan::neighbours;
+use anyhow::Context;
+use palette::named;
+
+use crate::manhattan::search;
+use crate::manhattan::Grid;
 use crate::manhattan::Position;
+use crate::parse::ParseError;
+use crate::render;
+use crate::render::Cell;
+use crate::render::Picture;
+use crate::solver::Solver;
+
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = Garden;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let map = Grid::parse_with(input, |c| match c {
+            '.' | '#' | 'S' => Ok(c),
+            c => Err(ParseError::char(c, format!("unexpected garden plot '{c}'"))),
+        })?;
+
+        Ok(Garden {
+            rocks: map.map(|c| *c == '#'),
+            start: map
+                .position(|c| *c == 'S')
+                .context("couldn't find starting plot")?,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let plots = reachable(input, 64);
+        render::show("Plots reachable in 64 steps:", || draw(input, &plots));
 
-pub fn solve() -> anyhow::Result<()> {
-    let mut n = 0;
-    let mut m = 0;
-
-    let input = std::fs::read_to_string("res/day21.txt")?;
-
-    let mut rocks = HashSet::new();
-
-    let mut curr = None;
-
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '.' => {}
-                '#' => {
-                    rocks.insert((i as isize, j as isize));
-                }
-                'S' => {
-                    curr = Some((i as isize, j as isize));
-                }
-                c => anyhow::bail!("unexpected garden plot '{c}'"),
-            }
-        }
-        n = i + 1;
-        m = m.max(line.len());
+        Ok(plots.len())
     }
 
-    let n = n as isize;
-    let m = m as isize;
-    let curr = curr.context("couldn't find starting plot")?;
+    // Part 2 isn't solved yet, so it has no example test either
+    fn part_2(_input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok("??")
+    }
+}
 
-    let mut cache = HashMap::new();
+pub struct Garden {
+    /// Whether each plot holds a rock
+    rocks: Grid<bool>,
+    start: Position,
+}
 
-    let plots = floodfill(curr, 64, &rocks, n, m, &mut cache);
+/// Garden plots that can be reached in exactly `steps` steps
+fn reachable(garden: &Garden, steps: usize) -> HashSet<Position> {
+    let Garden { rocks, start } = garden;
 
-    let part_1 = plots.len();
+    let plots = |pos: &Position| {
+        rocks
+            .neighbours(*pos)
+            .filter(|(next, _)| !rocks[*next])
+            .map(|(next, _)| next)
+            .collect::<Vec<_>>()
+    };
 
-    println!("Part 1: {part_1}\nPart 2: ??");
-    Ok(())
+    search::reachable(*start, plots, steps)
 }
 
-fn floodfill(
-    curr: Position,
-    steps: usize,
-    rocks: &HashSet<Position>,
-    n: isize,
-    m: isize,
-    cache: &mut HashMap<(Position, usize), HashSet<Position>>,
-) -> HashSet<Position> {
-    let mut ret = HashSet::new();
-
-    if let Some(cached) = cache.get(&(curr, steps)) {
-        return cached.clone();
-    } else if steps == 0 {
-        ret.insert(curr);
-        return ret;
-    }
+fn draw(garden: &Garden, plots: &HashSet<Position>) -> Picture {
+    let rock = Cell::new('#').fg(named::SLATEGRAY);
+    let plot = Cell::new('·').fg(named::DARKGREEN);
+    let reached = Cell::new('O').fg(named::LAWNGREEN);
+
+    let mut picture = Picture::new(&garden.rocks, |pos, is_rock| match is_rock {
+        true => rock,
+        false if plots.contains(&pos) => reached,
+        false => plot,
+    })
+    .legend(rock, "rock")
+    .legend(plot, "garden plot")
+    .legend(reached, "reachable garden plot")
+    .legend(Cell::new('S').fg(named::GOLD), "start");
+
+    picture.set(garden.start, Cell::new('S').fg(named::GOLD));
+    picture
+}
 
-    for ((i, j), _) in neighbours(curr) {
-        if i >= 0 && i < n && j >= 0 && j < m && !rocks.contains(&(i, j)) {
-            ret.extend(floodfill((i, j), steps - 1, rocks, n, m, cache));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    // Only part 1: part 2 is unimplemented
+    #[test]
+    fn example() {
+        let input = Day21::parse(EXAMPLE).unwrap();
+
+        assert_eq!(reachable(&input, 6).len(), 16);
     }
-
-    cache.insert((curr, steps), ret.clone());
-
-    ret
 }
@@ -1,62 +1,96 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-use crate::utils::position_neighbours;
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+use crate::solver::Solver;
 
-pub fn solve() -> anyhow::Result<()> {
-    let schematic: Vec<Vec<char>> = std::fs::read_to_string("res/day03.txt")?
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect();
+pub struct Day3;
 
+impl Solver for Day3 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let (part_1, _) = scan(input)?;
+
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let (_, gears_to_nums) = scan(input)?;
+
+        let part_2 = gears_to_nums
+            .into_values()
+            .filter(|nums| nums.len() == 2)
+            .map(|nums| nums.into_iter().product::<u32>())
+            .sum::<u32>();
+
+        Ok(part_2)
+    }
+}
+
+/// Returns the sum of all part numbers and the part numbers adjacent to each gear
+fn scan(schematic: &Grid<char>) -> anyhow::Result<(u32, HashMap<Position, Vec<u32>>)> {
     let mut number_str = String::new();
     let mut is_part = false;
     let mut adjecent_gears = HashSet::new();
     let mut part_1 = 0;
-    let mut gears_to_nums: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
-    for i in 0..schematic.len() {
-        for j in 0..schematic[i].len() {
-            if schematic[i][j].is_numeric() {
-                number_str.push(schematic[i][j]);
-                for (i, j) in position_neighbours(
-                    i,
-                    j,
-                    0,
-                    schematic.len() - 1,
-                    0,
-                    schematic[i].len() - 1,
-                    true,
-                ) {
-                    if !schematic[i][j].is_numeric() && schematic[i][j] != '.' {
-                        is_part = true;
-                    }
-
-                    if schematic[i][j] == '*' {
-                        adjecent_gears.insert((i, j));
-                    }
+    let mut gears_to_nums: HashMap<Position, Vec<u32>> = HashMap::new();
+    for (pos, c) in schematic.iter() {
+        if c.is_numeric() {
+            number_str.push(*c);
+            for (neighbour, _) in schematic.neighbours8(pos) {
+                let tile = schematic[neighbour];
+                if !tile.is_numeric() && tile != '.' {
+                    is_part = true;
                 }
-            } else {
-                if is_part {
-                    let num = number_str.parse::<u32>()?;
-                    part_1 += num;
-
-                    adjecent_gears
-                        .drain()
-                        .for_each(|gear| gears_to_nums.entry(gear).or_default().push(num));
+
+                if tile == '*' {
+                    adjecent_gears.insert(neighbour);
                 }
-                number_str.clear();
-                is_part = false;
             }
+        } else {
+            if is_part {
+                let num = number_str.parse::<u32>()?;
+                part_1 += num;
+
+                adjecent_gears
+                    .drain()
+                    .for_each(|gear| gears_to_nums.entry(gear).or_default().push(num));
+            }
+            number_str.clear();
+            is_part = false;
         }
     }
 
-    let part_2 = gears_to_nums
-        .into_values()
-        .filter(|nums| nums.len() == 2)
-        .map(|nums| nums.into_iter().product::<u32>())
-        .sum::<u32>();
+    Ok((part_1, gears_to_nums))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
 
-    Ok(())
+    #[test]
+    fn example() {
+        let input = Day3::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day3::part_1(&input).unwrap().to_string(), "4361");
+        assert_eq!(Day3::part_2(&input).unwrap().to_string(), "467835");
+    }
 }
@@ -1,58 +1,99 @@
 use std::collections::VecDeque;
 
-use anyhow::Context;
 use indexmap::IndexSet;
 
-pub fn solve() -> anyhow::Result<()> {
-    let input = std::fs::read_to_string("res/day04.txt")?;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-    let mut part_1 = 0;
-    let mut part_2 = 0;
-    let mut lookahead = VecDeque::new();
-    for line in input.lines() {
-        let (winners, numbers) = parse_line(line)?;
-        let n_winners = numbers.iter().filter(|n| winners.contains(*n)).count();
+pub struct Day4;
 
-        if n_winners > 0 {
-            part_1 += 2_u32.pow(n_winners as u32 - 1);
-        }
+impl Solver for Day4 {
+    /// Number of winning numbers on each card
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let cards = parse::lines(input, |line| {
+            let (winners, numbers) = parse_line(line)?;
+            Ok(numbers.iter().filter(|n| winners.contains(*n)).count())
+        })?;
+
+        Ok(cards)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let part_1 = input
+            .iter()
+            .filter(|n_winners| **n_winners > 0)
+            .map(|n_winners| 2_u32.pow(*n_winners as u32 - 1))
+            .sum::<u32>();
 
-        let n_cards: u32 = 1 + lookahead.pop_front().unwrap_or_default();
-        part_2 += n_cards;
-        for i in 0..n_winners {
-            if let Some(curr) = lookahead.get_mut(i) {
-                *curr += n_cards;
-            } else {
-                lookahead.push_back(n_cards);
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_2 = 0;
+        let mut lookahead = VecDeque::new();
+        for n_winners in input.iter() {
+            let n_cards: u32 = 1 + lookahead.pop_front().unwrap_or_default();
+            part_2 += n_cards;
+            for i in 0..*n_winners {
+                if let Some(curr) = lookahead.get_mut(i) {
+                    *curr += n_cards;
+                } else {
+                    lookahead.push_back(n_cards);
+                }
             }
         }
+
+        Ok(part_2)
     }
+}
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+fn parse_line(line: &str) -> Result<(IndexSet<u32>, IndexSet<u32>), ParseError> {
+    let (_, numbers) = line
+        .split_once(": ")
+        .ok_or_else(|| ParseError::new(line, "expected `Card <id>: `"))?;
+    let (winners, numbers) = numbers
+        .split_once(" | ")
+        .ok_or_else(|| ParseError::end(line, "expected ` | ` between the two lists of numbers"))?;
 
-    Ok(())
+    let numbers_in = |list: &str| {
+        parse::numbers(list)
+            .map(IndexSet::from_iter)
+            .map_err(|err| err.within(line, list))
+    };
+
+    Ok((numbers_in(winners)?, numbers_in(numbers)?))
 }
 
-fn parse_line(line: &str) -> anyhow::Result<(IndexSet<u32>, IndexSet<u32>)> {
-    let mut split = line
-        .split(": ")
-        .nth(1)
-        .map(|s| s.split(" | "))
-        .context("unexpected line format")?;
-
-    let winners = split
-        .next()
-        .context("unexpected line format")?
-        .split_whitespace()
-        .map(|s| s.parse::<u32>())
-        .collect::<Result<IndexSet<_>, _>>()?;
-
-    let numbers = split
-        .next()
-        .context("unexpected line format")?
-        .split_whitespace()
-        .map(|s| s.parse::<u32>())
-        .collect::<Result<IndexSet<_>, _>>()?;
-
-    Ok((winners, numbers))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn example() {
+        let input = Day4::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day4::part_1(&input).unwrap().to_string(), "13");
+        assert_eq!(Day4::part_2(&input).unwrap().to_string(), "30");
+    }
+
+    #[test]
+    fn parse_error_points_at_the_bad_number() {
+        let input = EXAMPLE.replace(" 21 53 ", " 21 x3 ");
+        let err = Day4::parse(&input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.column, 14);
+    }
 }
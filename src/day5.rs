@@ -1,60 +1,150 @@
-use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::str::FromStr;
 
-pub fn solve() -> anyhow::Result<()> {
-    let content = std::fs::read_to_string("res/day05.txt")?;
+use anyhow::Context;
 
-    let mut split = content.split("\n\n");
+use crate::manhattan::search;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-    let seeds = split
-        .next()
-        .and_then(|l| l.strip_prefix("seeds: "))
-        .context("missing seeds")?
-        .split_whitespace()
-        .map(|s| s.parse())
-        .collect::<Result<Vec<u64>, _>>()?;
+pub struct Day5;
 
-    let mut maps = split.map(|p| p.parse()).collect::<Result<Vec<Map>, _>>()?;
+impl Solver for Day5 {
+    type Input = Almanac;
 
-    let mut part_1 = u64::MAX;
-    for seed in seeds.iter() {
-        let mut mapped_seed = *seed;
-        for map in maps.iter() {
-            mapped_seed = map.map(mapped_seed);
-        }
-        part_1 = part_1.min(mapped_seed);
-    }
-
-    maps.reverse();
-    maps.iter_mut().for_each(|m| {
-        m.ranges
-            .iter_mut()
-            .for_each(|r| std::mem::swap(&mut r.source, &mut r.target))
-    });
-    let seeds = seeds
-        .chunks(2)
-        .map(|c| (c[0], c[0] + c[1]))
-        .collect::<Vec<_>>();
-
-    let mut part_2 = 80085;
-    for location in 0.. {
-        let mut seed = location;
-        for map in maps.iter() {
-            seed = map.map(seed);
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let mut blocks = parse::split_blocks(input).into_iter();
+        let (_, seeds_line) = blocks.next().unwrap_or_default();
+
+        let seeds = parse::labelled_numbers(seeds_line, "seeds:")
+            .map_err(|err| err.at_line(1, seeds_line))?;
+
+        let mut maps = HashMap::new();
+        for (start, block) in blocks {
+            let map = block.parse::<Map>().map_err(|err| err.below(start))?;
+            let categories = (map.source.clone(), map.target.clone());
+
+            if maps.insert(categories, map).is_some() {
+                let header = block.lines().next().unwrap_or_default();
+                Err(ParseError::new(header, "duplicate map").at_line(start + 1, header))?;
+            }
         }
 
-        if seeds.iter().any(|r| seed >= r.0 && seed < r.1) {
-            part_2 = location;
-            break;
+        let almanac = Almanac { seeds, maps };
+        if let Some(cycle) = almanac.find_cycle() {
+            anyhow::bail!("maps lead in a cycle: {}", cycle.join(" -> "));
         }
+
+        Ok(almanac)
     }
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let seed_to_location = input.path("seed", "location")?;
+
+        let part_1 = input
+            .seeds
+            .iter()
+            .map(|seed| seed_to_location.map(*seed))
+            .min()
+            .context("no seeds to plant")?;
 
-    Ok(())
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        anyhow::ensure!(
+            input.seeds.len() % 2 == 0,
+            "seed ranges should come in pairs of start and length"
+        );
+
+        let seeds = input
+            .seeds
+            .chunks(2)
+            .map(|c| (c[0], c[0] + c[1]))
+            .collect::<Vec<_>>();
+
+        let locations = input.path("seed", "location")?.map_intervals(seeds);
+
+        let part_2 = locations
+            .iter()
+            .map(|(start, _)| *start)
+            .min()
+            .context("no seeds to plant")?;
+
+        Ok(part_2)
+    }
 }
 
 #[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    /// Maps keyed by their source and target categories
+    maps: HashMap<(String, String), Map>,
+}
+
+impl Almanac {
+    /// Single map from the `from` category to the `to` one, composing the maps along the way
+    pub fn path(&self, from: &str, to: &str) -> anyhow::Result<Map> {
+        let (found, _) = search::bfs(
+            from.to_string(),
+            |category| category == to,
+            |category| self.targets(category),
+            None,
+        );
+        let (categories, _) = found.with_context(|| format!("no maps lead from {from} to {to}"))?;
+
+        Ok(categories
+            .windows(2)
+            .map(|pair| &self.maps[&(pair[0].clone(), pair[1].clone())])
+            .fold(Map::identity(from), |composed, map| composed.then(map)))
+    }
+
+    /// Maps the number `num` of the `from` category to the `to` one, e.g. seed 79 to humidity
+    pub fn lookup(&self, from: &str, num: u64, to: &str) -> anyhow::Result<u64> {
+        Ok(self.path(from, to)?.map(num))
+    }
+
+    /// Numbers of the `from` category ending up in one of the `intervals` of the `to` category,
+    /// e.g. the seeds ending up in some range of locations
+    pub fn sources(
+        &self,
+        from: &str,
+        to: &str,
+        intervals: &[Interval],
+    ) -> anyhow::Result<Vec<Interval>> {
+        Ok(self.path(from, to)?.preimage(intervals))
+    }
+
+    /// Categories there's a map to from `category`
+    fn targets(&self, category: &str) -> Vec<String> {
+        self.maps
+            .keys()
+            .filter(|(source, _)| source == category)
+            .map(|(_, target)| target.clone())
+            .collect()
+    }
+
+    /// Categories leading back to where they started through the maps, if any do
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        self.maps.keys().find_map(|(source, target)| {
+            let (found, _) = search::bfs(
+                target.clone(),
+                |category| category == source,
+                |category| self.targets(category),
+                None,
+            );
+
+            found.map(|(mut cycle, _)| {
+                cycle.insert(0, source.clone());
+                cycle
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 struct Range {
     source: u64,
     target: u64,
@@ -62,14 +152,20 @@ struct Range {
 }
 
 impl FromStr for Range {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split_whitespace();
+        let mut field = |name| {
+            let field = split
+                .next()
+                .ok_or_else(|| ParseError::end(s, format!("{name} not found")))?;
+            parse::number(field).map_err(|err| err.within(s, field))
+        };
 
-        let target = split.next().context("target not found")?.parse()?;
-        let source = split.next().context("source not found")?.parse()?;
-        let length = split.next().context("length not found")?.parse()?;
+        let target = field("target")?;
+        let source = field("source")?;
+        let length = field("length")?;
 
         Ok(Self {
             source,
@@ -79,46 +175,386 @@ impl FromStr for Range {
     }
 }
 
+/// Half-open interval of numbers, from the first one up to but excluding the second one
+type Interval = (u64, u64);
+
 impl Range {
-    fn map(&self, num: u64) -> u64 {
+    fn map(&self, num: u64) -> Option<u64> {
         num.checked_sub(self.source)
             .filter(|n| *n < self.length)
             .map(|n| n + self.target)
-            .unwrap_or(num)
+    }
+
+    /// The part of this range mapping the numbers of `interval`, if any
+    fn restrict(&self, (start, end): Interval) -> Option<Range> {
+        let start = start.max(self.source);
+        let end = end.min(self.source + self.length);
+
+        (start < end).then(|| Range {
+            source: start,
+            target: start - self.source + self.target,
+            length: end - start,
+        })
+    }
+
+    fn targets(&self) -> Interval {
+        (self.target, self.target + self.length)
+    }
+
+    fn is_identity(&self) -> bool {
+        self.source == self.target
+    }
+
+    /// Splits `interval` into the part covered by this range, already mapped, and the parts
+    /// before and after it, which aren't
+    fn split(&self, (start, end): Interval) -> (Option<Interval>, [Option<Interval>; 2]) {
+        let source_end = self.source + self.length;
+        let mapped = self.restrict((start, end)).map(|covered| covered.targets());
+
+        let before = (start, end.min(self.source));
+        let after = (start.max(source_end), end);
+
+        (
+            mapped,
+            [
+                (before.0 < before.1).then_some(before),
+                (after.0 < after.1).then_some(after),
+            ],
+        )
     }
 }
 
-#[derive(Debug)]
-struct Map {
+/// Piecewise function from numbers of the `source` category to numbers of the `target` one,
+/// moving the numbers of each of its ranges, and leaving the others unchanged. Where ranges
+/// overlap, the first one applies.
+#[derive(Debug, Clone)]
+pub struct Map {
+    pub source: String,
+    pub target: String,
     ranges: Vec<Range>,
 }
 
 impl FromStr for Map {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.lines();
+        let (name, ranges) = s.split_once('\n').unwrap_or((s, ""));
+        let name = name.trim_end();
 
-        split.next().context("missing map name")?;
+        let (source, target) = name
+            .strip_suffix(" map:")
+            .and_then(|categories| categories.split_once("-to-"))
+            .ok_or_else(|| {
+                ParseError::new(name, "expected `<source>-to-<target> map:`").at_line(1, name)
+            })?;
 
-        let ranges = split.map(|l| l.parse()).collect::<Result<Vec<_>, _>>()?;
+        let ranges = parse::lines(ranges, str::parse).map_err(|err| err.below(1))?;
 
-        Ok(Self { ranges })
+        Ok(Self {
+            source: source.to_string(),
+            target: target.to_string(),
+            ranges,
+        })
     }
 }
 
 impl Map {
-    fn map(&self, num: u64) -> u64 {
-        self.ranges
-            .iter()
-            .find_map(|r| {
-                let mapped = r.map(num);
-                if mapped == num {
-                    None
-                } else {
-                    Some(mapped)
+    /// Map leaving every number of `category` unchanged
+    pub fn identity(category: &str) -> Self {
+        Self {
+            source: category.to_string(),
+            target: category.to_string(),
+            ranges: vec![],
+        }
+    }
+
+    pub fn map(&self, num: u64) -> u64 {
+        self.ranges.iter().find_map(|r| r.map(num)).unwrap_or(num)
+    }
+
+    /// Maps every number of `intervals`, keeping them as intervals: their parts covered by a range
+    /// are moved by it, and the rest pass through unchanged
+    pub fn map_intervals(&self, intervals: Vec<Interval>) -> Vec<Interval> {
+        let mut mapped = vec![];
+        let mut unmapped = intervals;
+
+        for range in self.ranges.iter() {
+            let mut rest = Vec::with_capacity(unmapped.len());
+
+            for interval in unmapped {
+                let (covered, uncovered) = range.split(interval);
+                mapped.extend(covered);
+                rest.extend(uncovered.into_iter().flatten());
+            }
+
+            unmapped = rest;
+        }
+
+        mapped.extend(unmapped);
+        mapped
+    }
+
+    /// Map sending numbers through this map and then through `next`, as a single map
+    pub fn then(&self, next: &Map) -> Map {
+        let next_pieces = next.pieces();
+        let mut ranges: Vec<Range> = vec![];
+
+        for piece in self.pieces() {
+            for next_piece in next_pieces.iter() {
+                let Some(part) = next_piece.restrict(piece.targets()) else {
+                    continue;
+                };
+
+                let range = Range {
+                    source: part.source - piece.target + piece.source,
+                    ..part
+                };
+                if range.is_identity() {
+                    continue;
                 }
-            })
-            .unwrap_or(num)
+
+                // Merge pieces moving numbers by the same amount, so the dump stays readable
+                match ranges.last_mut() {
+                    Some(last)
+                        if last.source + last.length == range.source
+                            && last.target + last.length == range.target =>
+                    {
+                        last.length += range.length;
+                    }
+                    _ => ranges.push(range),
+                }
+            }
+        }
+
+        Map {
+            source: self.source.clone(),
+            target: next.target.clone(),
+            ranges,
+        }
+    }
+
+    /// Map undoing this one, if this one never maps two numbers to the same one
+    pub fn inverse(&self) -> Option<Map> {
+        let mut pieces = self.pieces();
+        pieces.sort_by_key(|piece| piece.target);
+
+        // The pieces cover every number exactly once, so the map is one-to-one if their targets
+        // don't overlap either
+        let one_to_one = pieces
+            .windows(2)
+            .all(|pair| pair[0].target + pair[0].length <= pair[1].target);
+
+        one_to_one.then(|| Map {
+            source: self.target.clone(),
+            target: self.source.clone(),
+            ranges: pieces
+                .into_iter()
+                .filter(|piece| !piece.is_identity())
+                .map(|piece| Range {
+                    source: piece.target,
+                    target: piece.source,
+                    length: piece.length,
+                })
+                .collect(),
+        })
+    }
+
+    /// Every number mapped into one of `intervals`, as intervals
+    pub fn preimage(&self, intervals: &[Interval]) -> Vec<Interval> {
+        let mut preimage = vec![];
+
+        for piece in self.pieces() {
+            let inverse = Range {
+                source: piece.target,
+                target: piece.source,
+                length: piece.length,
+            };
+
+            preimage.extend(
+                intervals
+                    .iter()
+                    .filter_map(|interval| inverse.restrict(*interval))
+                    .map(|part| part.targets()),
+            );
+        }
+
+        preimage
+    }
+
+    /// The map as pieces covering every number below `u64::MAX` exactly once, sorted by source:
+    /// the parts of its ranges that apply, and the gaps between them mapping numbers to themselves
+    fn pieces(&self) -> Vec<Range> {
+        let mut pieces = vec![];
+        let mut uncovered = vec![(0, u64::MAX)];
+
+        for range in self.ranges.iter() {
+            let mut rest = Vec::with_capacity(uncovered.len());
+
+            for interval in uncovered {
+                pieces.extend(range.restrict(interval));
+
+                let (_, outside) = range.split(interval);
+                rest.extend(outside.into_iter().flatten());
+            }
+
+            uncovered = rest;
+        }
+
+        pieces.extend(uncovered.into_iter().map(|(start, end)| Range {
+            source: start,
+            target: start,
+            length: end - start,
+        }));
+        pieces.sort_by_key(|piece| piece.source);
+
+        pieces
+    }
+}
+
+/// Lists what every interval of numbers is mapped to under a header naming the categories, e.g.
+/// `50..98 -> 52..100 (+2)`
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-to-{} map:", self.source, self.target)?;
+
+        for piece in self.pieces() {
+            let (start, end) = piece.targets();
+            let offset = piece.target as i128 - piece.source as i128;
+            write!(
+                f,
+                "\n{}..{} -> {start}..{end} ({offset:+})",
+                piece.source,
+                piece.source + piece.length
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn example() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day5::part_1(&input).unwrap().to_string(), "35");
+        assert_eq!(Day5::part_2(&input).unwrap().to_string(), "46");
+    }
+
+    #[test]
+    fn composition() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        let seed_to_soil = input.path("seed", "soil").unwrap();
+        let soil_to_fertilizer = input.path("soil", "fertilizer").unwrap();
+        let composed = seed_to_soil.then(&soil_to_fertilizer);
+
+        for seed in 0..120 {
+            assert_eq!(
+                composed.map(seed),
+                soil_to_fertilizer.map(seed_to_soil.map(seed))
+            );
+        }
+
+        let dump = input.path("seed", "location").unwrap().to_string();
+        assert_eq!(
+            dump.lines().take(2).collect::<Vec<_>>(),
+            ["seed-to-location map:", "0..14 -> 22..36 (+22)"]
+        );
+    }
+
+    #[test]
+    fn inversion() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+        let seed_to_soil = input.path("seed", "soil").unwrap();
+        let soil_to_seed = seed_to_soil.inverse().unwrap();
+
+        for seed in 0..120 {
+            assert_eq!(soil_to_seed.map(seed_to_soil.map(seed)), seed);
+        }
+
+        // Both 0 and 10 are mapped to 0, and nothing to 10
+        let squash = "a-to-b map:\n0 10 5".parse::<Map>().unwrap();
+        let mut preimage = squash.preimage(&[(0, 1), (10, 12)]);
+        preimage.sort();
+
+        assert!(squash.inverse().is_none());
+        assert_eq!(preimage, vec![(0, 1), (10, 11)]);
+    }
+
+    #[test]
+    fn queries() {
+        let input = Day5::parse(EXAMPLE).unwrap();
+
+        assert_eq!(input.lookup("seed", 79, "humidity").unwrap(), 78);
+        assert_eq!(input.lookup("water", 81, "water").unwrap(), 81);
+        assert!(input.lookup("location", 82, "seed").is_err());
+
+        let mut seeds = input.sources("seed", "location", &[(46, 47)]).unwrap();
+        seeds.sort();
+        assert!(seeds.iter().any(|(start, end)| (start..end).contains(&&82)));
+    }
+
+    #[test]
+    fn bad_category_graphs() {
+        let duplicate = "seeds: 1\n\na-to-b map:\n1 2 3\n\na-to-b map:\n4 5 6";
+        let err = Day5::parse(duplicate).unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().line, Some(6));
+
+        let cycle = "seeds: 1\n\na-to-b map:\n1 2 3\n\nb-to-a map:\n4 5 6";
+        assert!(Day5::parse(cycle)
+            .unwrap_err()
+            .to_string()
+            .starts_with("maps lead in a cycle"));
+    }
+
+    #[test]
+    fn intervals_are_split_across_ranges() {
+        let map = "a-to-b map:\n50 98 2\n52 50 48".parse::<Map>().unwrap();
+
+        let mut mapped = map.map_intervals(vec![(40, 60), (97, 110)]);
+        mapped.sort();
+
+        assert_eq!(
+            mapped,
+            vec![(40, 50), (50, 52), (52, 62), (99, 100), (100, 110)]
+        );
     }
 }
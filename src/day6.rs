@@ -1,54 +1,54 @@
-use anyhow::Context;
-
-pub fn solve() -> anyhow::Result<()> {
-    let content = std::fs::read_to_string("res/day06.txt")?;
-    let mut lines = content.lines();
-
-    let times = lines
-        .next()
-        .context("missing times")?
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse())
-        .collect::<Result<Vec<usize>, _>>()?;
-
-    let distances = lines
-        .next()
-        .context("missing distances")?
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse())
-        .collect::<Result<Vec<usize>, _>>()?;
-
-    let part_1 = times
-        .iter()
-        .cloned()
-        .zip(distances.iter().cloned())
-        .map(|(time, dist)| count(time, dist))
-        .product::<usize>();
-
-    let mut lines = content.lines();
-
-    let time = lines
-        .next()
-        .context("missing times")?
-        .strip_prefix("Time:")
-        .context("invalid times")?
-        .replace(' ', "")
-        .parse()?;
-
-    let distance = lines
-        .next()
-        .context("missing distances")?
-        .strip_prefix("Distance:")
-        .context("invalid distances")?
-        .replace(' ', "")
-        .parse()?;
-
-    let part_2 = count(time, distance);
-
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
+
+pub struct Day6;
+
+impl Solver for Day6 {
+    type Input = Races;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let mut lines = input.lines();
+
+        let mut next_line = |number, label| {
+            let line = lines.next().unwrap_or_default();
+            parse_line(line, label).map_err(|err| err.at_line(number, line))
+        };
+
+        let (times, time) = next_line(1, "Time:")?;
+        let (distances, distance) = next_line(2, "Distance:")?;
+
+        Ok(Races {
+            times,
+            distances,
+            time,
+            distance,
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let part_1 = input
+            .times
+            .iter()
+            .cloned()
+            .zip(input.distances.iter().cloned())
+            .map(|(time, dist)| count(time, dist))
+            .product::<usize>();
+
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(count(input.time, input.distance))
+    }
+}
+
+/// Race records, read both as separate races and as a single race with bad kerning
+pub struct Races {
+    times: Vec<usize>,
+    distances: Vec<usize>,
+    time: usize,
+    distance: usize,
 }
 
 fn count(time: usize, dist: usize) -> usize {
@@ -67,3 +67,31 @@ fn count(time: usize, dist: usize) -> usize {
 
     winning_strategies
 }
+
+/// Parses a line of `label`led numbers both as separate numbers and, ignoring the spaces, as one
+fn parse_line(line: &str, label: &str) -> Result<(Vec<usize>, usize), ParseError> {
+    let numbers = parse::labelled(line, label)?;
+
+    let separate = parse::numbers(numbers).map_err(|err| err.within(line, numbers))?;
+    let joined = parse::number(&numbers.replace(' ', ""))
+        .map_err(|err| ParseError::at(line, label.len(), err.message))?;
+
+    Ok((separate, joined))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn example() {
+        let input = Day6::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day6::part_1(&input).unwrap().to_string(), "288");
+        assert_eq!(Day6::part_2(&input).unwrap().to_string(), "71503");
+    }
+}
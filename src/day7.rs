@@ -2,102 +2,285 @@ use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::str::FromStr;
 
-use anyhow::Context;
+use crate::diag;
+use crate::diagnostics;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 
-pub fn solve() -> anyhow::Result<()> {
-    let mut hands = vec![];
+pub struct Day7;
 
-    for line in std::fs::read_to_string("res/day07.txt")?.lines() {
-        let hand = line.parse::<Hand>()?;
-        hands.push(hand);
+impl Solver for Day7 {
+    type Input = Vec<Hand>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse::lines(input, str::parse)?)
     }
 
-    hands.sort();
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(total_winnings(
+            input,
+            &Rules::STANDARD,
+            "Part 1 (J is a jack)",
+        ))
+    }
 
-    let part_2 = hands
-        .iter()
-        .enumerate()
-        .map(|(ord, hand)| hand.bid * (ord + 1))
-        .sum::<usize>();
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(total_winnings(
+            input,
+            &Rules::JOKERS_WILD,
+            "Part 2 (jokers wild)",
+        ))
+    }
+}
 
-    println!("Part 2: {part_2}");
+/// Sum of every hand's bid multiplied by its rank under `rules`, listing the hands in rank order
+/// under a `title` when explaining
+fn total_winnings(hands: &[Hand], rules: &Rules, title: &str) -> usize {
+    let explaining = diagnostics::explaining();
+    if explaining {
+        diag!("{title}");
+        diag!(
+            "{:>5}  {:<40}  {:>5}  {:>9}",
+            "rank",
+            "hand",
+            "bid",
+            "winnings"
+        );
+    }
 
-    Ok(())
-}
+    let mut total = 0;
+    for (ord, hand) in rank(hands, rules).iter().enumerate() {
+        let winnings = hand.bid * (ord + 1);
+        total += winnings;
 
-struct Hand {
-    cards: Vec<Card>,
-    combos: Vec<usize>,
-    bid: usize,
+        if explaining {
+            let explanation = hand.explain(rules);
+            diag!(
+                "{:>5}  {explanation:<40}  {:>5}  {winnings:>9}",
+                ord + 1,
+                hand.bid
+            );
+        }
+    }
+
+    total
 }
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.cards == other.cards
-    }
+/// Hands from weakest to strongest under `rules`
+fn rank<'a>(hands: &'a [Hand], rules: &Rules) -> Vec<&'a Hand> {
+    let mut ranked = hands.iter().collect::<Vec<_>>();
+    ranked.sort_by_cached_key(|hand| hand.strength(rules));
+    ranked
 }
 
-impl Eq for Hand {}
+/// How jokers are played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JokerPolicy {
+    /// `J` is a jack, like any other card
+    Plain,
+    /// `J` stands in for whichever card makes the hand's type strongest
+    Wild,
+}
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// Ranking of the cards, along with how jokers are played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Every card, from weakest to strongest
+    pub ranking: [Card; 13],
+    pub jokers: JokerPolicy,
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let combos_cmp = self.combos.cmp(&other.combos);
+impl Rules {
+    /// Part 1 rules, with jacks ranked between tens and queens
+    pub const STANDARD: Self = Self {
+        ranking: [
+            Card::N2,
+            Card::N3,
+            Card::N4,
+            Card::N5,
+            Card::N6,
+            Card::N7,
+            Card::N8,
+            Card::N9,
+            Card::T,
+            Card::J,
+            Card::Q,
+            Card::K,
+            Card::A,
+        ],
+        jokers: JokerPolicy::Plain,
+    };
 
-        if !combos_cmp.is_eq() {
-            return combos_cmp;
-        }
+    /// Part 2 rules, with jokers wild but ranked below every other card
+    pub const JOKERS_WILD: Self = Self {
+        ranking: [
+            Card::J,
+            Card::N2,
+            Card::N3,
+            Card::N4,
+            Card::N5,
+            Card::N6,
+            Card::N7,
+            Card::N8,
+            Card::N9,
+            Card::T,
+            Card::Q,
+            Card::K,
+            Card::A,
+        ],
+        jokers: JokerPolicy::Wild,
+    };
 
-        self.cards.cmp(&other.cards)
+    /// Position of `card` in the ranking, higher being stronger
+    pub fn strength(&self, card: Card) -> usize {
+        self.ranking
+            .iter()
+            .position(|c| *c == card)
+            .unwrap_or_default()
     }
 }
 
-impl FromStr for Hand {
-    type Err = anyhow::Error;
+pub struct Hand {
+    cards: Vec<Card>,
+    bid: usize,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split_whitespace();
+impl Hand {
+    pub fn hand_type(&self, rules: &Rules) -> HandType {
+        HandType::from_combos(&self.combos(rules))
+    }
 
-        let cards = split
-            .next()
-            .context("hand not found")?
-            .chars()
-            .map(|c| c.try_into())
-            .collect::<Result<Vec<_>, _>>()?;
+    /// The hand's cards and type, along with the card its jokers stand for if they're wild, e.g.
+    /// `KTJJT: four of a kind (J as T)`
+    pub fn explain(&self, rules: &Rules) -> String {
+        let cards = self
+            .cards
+            .iter()
+            .map(|card| char::from(*card))
+            .collect::<String>();
+        let hand_type = self.hand_type(rules);
 
-        let bid = split.next().context("bid not found")?.parse()?;
+        match self.joker_replacement(rules) {
+            Some(card) => format!("{cards}: {hand_type} (J as {})", char::from(card)),
+            None => format!("{cards}: {hand_type}"),
+        }
+    }
 
-        let mut combos = cards.iter().fold(HashMap::new(), |mut acc, e| {
+    /// Card the hand's jokers stand for, if they're wild and it has any: the most common, and
+    /// then strongest, other card
+    fn joker_replacement(&self, rules: &Rules) -> Option<Card> {
+        if rules.jokers != JokerPolicy::Wild || !self.cards.contains(&Card::J) {
+            return None;
+        }
+
+        let replacement = self
+            .counts()
+            .into_iter()
+            .filter(|(card, _)| *card != Card::J)
+            .max_by_key(|(card, count)| (*count, rules.strength(*card)))
+            .map(|(card, _)| card)
+            .unwrap_or(Card::A); // If all we have is jokers, make them all aces
+
+        Some(replacement)
+    }
+
+    fn counts(&self) -> HashMap<Card, usize> {
+        self.cards.iter().fold(HashMap::new(), |mut acc, e| {
             *acc.entry(*e).or_default() += 1;
             acc
-        });
+        })
+    }
 
-        if let Some(n_jokers) = combos.remove(&Card::J) {
-            // Find the non-joker card with the most occurences and highest value
-            let replacement = combos
-                .iter()
-                .max_by_key(|(card, count)| (**count, **card))
-                .map(|(card, _)| *card)
-                .unwrap_or(Card::A); // If all we have is jokers, make them all aces
+    /// Number of cards of each kind, from the most common kind to the least common one, with
+    /// wild jokers counted as the card they stand for
+    fn combos(&self, rules: &Rules) -> Vec<usize> {
+        let mut combos = self.counts();
 
+        if let Some(replacement) = self.joker_replacement(rules) {
+            let n_jokers = combos.remove(&Card::J).unwrap_or_default();
             *combos.entry(replacement).or_default() += n_jokers;
         }
 
         let mut combos = combos.into_values().collect::<Vec<_>>();
         combos.sort_by_key(|k| Reverse(*k));
+        combos
+    }
 
-        Ok(Self { cards, combos, bid })
+    /// Key ordering hands from weakest to strongest: by their type, then card by card
+    fn strength(&self, rules: &Rules) -> (HandType, Vec<usize>) {
+        let cards = self
+            .cards
+            .iter()
+            .map(|card| rules.strength(*card))
+            .collect();
+
+        (self.hand_type(rules), cards)
     }
 }
 
+/// Kind of hand, from weakest to strongest
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl HandType {
+    /// Type of a hand with `combos` cards of each kind, from the most common kind to the least
+    /// common one
+    fn from_combos(combos: &[usize]) -> Self {
+        match combos {
+            [5, ..] => Self::FiveOfAKind,
+            [4, ..] => Self::FourOfAKind,
+            [3, 2, ..] => Self::FullHouse,
+            [3, ..] => Self::ThreeOfAKind,
+            [2, 2, ..] => Self::TwoPair,
+            [2, ..] => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+impl std::fmt::Display for HandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::HighCard => "high card",
+            Self::OnePair => "one pair",
+            Self::TwoPair => "two pair",
+            Self::ThreeOfAKind => "three of a kind",
+            Self::FullHouse => "full house",
+            Self::FourOfAKind => "four of a kind",
+            Self::FiveOfAKind => "five of a kind",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hand, bid) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::end(s, "expected a hand and a bid"))?;
+
+        let cards = parse::each_char(hand, Card::try_from).map_err(|err| err.within(s, hand))?;
+        let bid = parse::number(bid).map_err(|err| err.within(s, bid))?;
+
+        Ok(Self { cards, bid })
+    }
+}
+
+/// Card of the deck, whose strength depends on the [`Rules`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Card {
-    J,
     N2,
     N3,
     N4,
@@ -107,13 +290,34 @@ pub enum Card {
     N8,
     N9,
     T,
+    J,
     Q,
     K,
     A,
 }
 
+impl From<Card> for char {
+    fn from(card: Card) -> Self {
+        match card {
+            Card::A => 'A',
+            Card::K => 'K',
+            Card::Q => 'Q',
+            Card::J => 'J',
+            Card::T => 'T',
+            Card::N9 => '9',
+            Card::N8 => '8',
+            Card::N7 => '7',
+            Card::N6 => '6',
+            Card::N5 => '5',
+            Card::N4 => '4',
+            Card::N3 => '3',
+            Card::N2 => '2',
+        }
+    }
+}
+
 impl TryFrom<char> for Card {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         Ok(match value {
@@ -130,7 +334,74 @@ impl TryFrom<char> for Card {
             '4' => Self::N4,
             '3' => Self::N3,
             '2' => Self::N2,
-            c => anyhow::bail!("`{c}` is not a valid card"),
+            c => return Err(ParseError::char(c, format!("`{c}` is not a valid card"))),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn example() {
+        let input = Day7::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day7::part_1(&input).unwrap().to_string(), "6440");
+        assert_eq!(Day7::part_2(&input).unwrap().to_string(), "5905");
+    }
+
+    #[test]
+    fn rules() {
+        let hand = "T55J5 684".parse::<Hand>().unwrap();
+        let jokers = "JJJJJ 1".parse::<Hand>().unwrap();
+
+        assert_eq!(hand.combos(&Rules::STANDARD), [3, 1, 1]);
+        assert_eq!(hand.combos(&Rules::JOKERS_WILD), [4, 1]);
+        assert_eq!(jokers.combos(&Rules::JOKERS_WILD), [5]);
+
+        let jack = "J2345 1".parse::<Hand>().unwrap();
+        let ten = "T2345 1".parse::<Hand>().unwrap();
+        assert!(jack.strength(&Rules::STANDARD) > ten.strength(&Rules::STANDARD));
+        assert!(jack.strength(&Rules::JOKERS_WILD) > ten.strength(&Rules::JOKERS_WILD));
+    }
+
+    #[test]
+    fn hand_types() {
+        let hand = |s: &str| s.parse::<Hand>().unwrap();
+
+        assert_eq!(
+            hand("32T3K 1").hand_type(&Rules::STANDARD),
+            HandType::OnePair
+        );
+        assert_eq!(
+            hand("KK677 1").hand_type(&Rules::STANDARD),
+            HandType::TwoPair
+        );
+        assert_eq!(
+            hand("23332 1").hand_type(&Rules::STANDARD),
+            HandType::FullHouse
+        );
+        assert_eq!(
+            hand("23456 1").hand_type(&Rules::JOKERS_WILD),
+            HandType::HighCard
+        );
+
+        assert_eq!(hand("KTJJT 1").explain(&Rules::STANDARD), "KTJJT: two pair");
+        assert_eq!(
+            hand("KTJJT 1").explain(&Rules::JOKERS_WILD),
+            "KTJJT: four of a kind (J as T)"
+        );
+        assert_eq!(
+            hand("JJJJJ 1").explain(&Rules::JOKERS_WILD),
+            "JJJJJ: five of a kind (J as A)"
+        );
+    }
+}
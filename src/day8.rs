@@ -1,78 +1,159 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::Context;
 
-use crate::utils::lcm_many;
+use crate::cycle;
+use crate::cycle::Cycle;
+use crate::diag;
+use crate::parse;
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use crate::utils::align_cycles;
 
-pub fn solve() -> anyhow::Result<()> {
-    let content = std::fs::read_to_string("res/day08.txt")?;
+pub struct Day8;
 
-    let mut lines = content.lines();
+impl Solver for Day8 {
+    type Input = Network;
 
-    let directions = lines
-        .next()
-        .context("no directions")?
-        .chars()
-        .map(|c| c.try_into())
-        .collect::<Result<Vec<Direction>, _>>()?;
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let blocks = parse::split_blocks(input);
+        let [(directions_start, directions_line), (nodes_start, nodes)] = blocks[..] else {
+            anyhow::bail!("expected directions and nodes, separated by a blank line");
+        };
 
-    let nodes = lines
-        .skip(1)
-        .map(|l| l.parse::<Node>().map(|node| (node.name.clone(), node)))
-        .collect::<Result<HashMap<_, _>, _>>()?;
+        let directions = parse::each_char(directions_line, Direction::try_from)
+            .map_err(|err| err.at_line(directions_start + 1, directions_line))?;
 
-    let mut curr = nodes.get("AAA").context("missing node AAA")?;
-    let mut part_1 = 0;
-    for dir in std::iter::repeat(directions.iter()).flat_map(|i| i.into_iter()) {
-        if &curr.name == "ZZZ" {
-            break;
-        }
+        let nodes = parse::lines(nodes, |line| {
+            let node = line.parse::<Node>()?;
+            Ok((node.name.clone(), node))
+        })
+        .map_err(|err| err.below(nodes_start))?
+        .into_iter()
+        .collect();
 
-        curr = match dir {
-            Direction::Left => nodes.get(&curr.left).context("couldn't find node")?,
-            Direction::Right => nodes.get(&curr.right).context("couldn't find node")?,
-        };
-        part_1 += 1;
+        Ok(Network { directions, nodes })
     }
 
-    let mut currs = nodes
-        .values()
-        .filter(|n| n.name.ends_with('A'))
-        .collect::<Vec<_>>();
-
-    let mut part_2 = 0;
-    let mut periods = vec![None; currs.len()];
-    'outer: loop {
-        for dir in directions.iter() {
-            if periods.iter().all(|p| p.is_some()) {
-                break 'outer;
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let Network { directions, nodes } = input;
+
+        let mut curr = nodes.get("AAA").context("missing node AAA")?;
+        let mut part_1 = 0;
+        for dir in std::iter::repeat(directions.iter()).flat_map(|i| i.into_iter()) {
+            if &curr.name == "ZZZ" {
+                break;
             }
 
-            let mut next_currs = Vec::with_capacity(currs.len());
-            for (curr_idx, curr) in currs.iter().enumerate() {
-                let next_curr = match dir {
-                    Direction::Left => nodes.get(&curr.left).context("couldn't find node")?,
-                    Direction::Right => nodes.get(&curr.right).context("couldn't find node")?,
-                };
+            curr = match dir {
+                Direction::Left => nodes.get(&curr.left).context("couldn't find node")?,
+                Direction::Right => nodes.get(&curr.right).context("couldn't find node")?,
+            };
+            part_1 += 1;
+        }
 
-                if next_curr.name.ends_with('Z') {
-                    periods[curr_idx] = Some(part_2 + 1);
-                }
+        Ok(part_1)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let Network { directions, nodes } = input;
 
-                next_currs.push(next_curr);
+        anyhow::ensure!(!directions.is_empty(), "no directions to follow");
+        for node in nodes.values() {
+            for next in [&node.left, &node.right] {
+                anyhow::ensure!(nodes.contains_key(next), "couldn't find node {next}");
             }
-            currs = next_currs;
-            part_2 += 1;
         }
+
+        // A ghost's state is its node along with where it is in the directions, so it loops once
+        // one of those comes back. It can reach Z nodes before the loop, or any number of times
+        // within it.
+        let step = |(name, idx): &(&str, usize)| {
+            let node = &nodes[*name];
+            let next = match directions[*idx] {
+                Direction::Left => &node.left,
+                Direction::Right => &node.right,
+            };
+            (next.as_str(), (idx + 1) % directions.len())
+        };
+
+        let mut ghosts = vec![];
+        for start in nodes.keys().filter(|name| name.ends_with('A')) {
+            let (cycle, states) = cycle::with_history((start.as_str(), 0), step);
+            let arrivals = states
+                .iter()
+                .enumerate()
+                .filter(|(_, (name, _))| name.ends_with('Z'))
+                .map(|(steps, _)| steps)
+                .collect::<Vec<_>>();
+            diag!("{start}: {cycle:?}, Z nodes after {arrivals:?} steps");
+
+            ghosts.push(Ghost {
+                cycle,
+                arrivals: arrivals.into_iter().collect(),
+            });
+        }
+
+        first_meeting(&ghosts).context("the ghosts never all reach a Z node at once")
     }
+}
+
+/// Where a ghost reaches Z nodes: `arrivals` are the steps up to the end of the first pass
+/// through its `cycle` at which it's on one
+struct Ghost {
+    cycle: Cycle,
+    arrivals: BTreeSet<usize>,
+}
+
+impl Ghost {
+    fn on_z_node(&self, steps: usize) -> bool {
+        self.arrivals.contains(&self.cycle.reduce(steps))
+    }
+
+    /// Arrivals that repeat with the cycle, as `(offset, period)`
+    fn repeating(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.arrivals
+            .range(self.cycle.prefix..)
+            .map(|&offset| (offset, self.cycle.period))
+    }
+}
 
-    let periods = periods.into_iter().flatten().collect::<Vec<_>>();
-    let lcm = lcm_many(&periods);
+/// First step at which every ghost is on a Z node: either one a ghost only reaches before its
+/// loop, or one that every ghost reaches on each pass through theirs
+fn first_meeting(ghosts: &[Ghost]) -> Option<u128> {
+    let before_loops = ghosts
+        .iter()
+        .flat_map(|ghost| ghost.arrivals.range(..ghost.cycle.prefix))
+        .filter(|&&steps| ghosts.iter().all(|ghost| ghost.on_z_node(steps)))
+        .map(|&steps| steps as u128)
+        .min();
 
-    println!("Part 1: {part_1}\nPart 2: {periods:?} => {lcm}");
+    // Try every combination of one repeating arrival per ghost
+    let combinations = ghosts.iter().fold(vec![vec![]], |combinations, ghost| {
+        combinations
+            .iter()
+            .flat_map(|combination| {
+                ghost.repeating().map(move |arrival| {
+                    let mut combination: Vec<(usize, usize)> = combination.clone();
+                    combination.push(arrival);
+                    combination
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    let within_loops = combinations
+        .iter()
+        .filter_map(|combination| align_cycles(combination))
+        .min();
 
-    Ok(())
+    before_loops.into_iter().chain(within_loops).min()
+}
+
+pub struct Network {
+    directions: Vec<Direction>,
+    nodes: HashMap<String, Node>,
 }
 
 enum Direction {
@@ -81,13 +162,18 @@ enum Direction {
 }
 
 impl TryFrom<char> for Direction {
-    type Error = anyhow::Error;
+    type Error = ParseError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         Ok(match value {
             'L' => Self::Left,
             'R' => Self::Right,
-            c => anyhow::bail!("`{c}` is not a valid direction character"),
+            c => {
+                return Err(ParseError::char(
+                    c,
+                    format!("`{c}` is not a valid direction character"),
+                ))
+            }
         })
     }
 }
@@ -100,12 +186,91 @@ struct Node {
 }
 
 impl FromStr for Node {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (name, left, right) = sscanf::sscanf!(s, "{} = ({}, {})", String, String, String)
-            .map_err(|e| anyhow::Error::msg(e.to_string()))?;
+            .map_err(|_| ParseError::new(s, "expected `<node> = (<left>, <right>)`"))?;
 
         Ok(Self { name, left, right })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part_1() {
+        const EXAMPLE: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+        const REPEATED: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+        let input = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(Day8::part_1(&input).unwrap().to_string(), "2");
+
+        let input = Day8::parse(REPEATED).unwrap();
+        assert_eq!(Day8::part_1(&input).unwrap().to_string(), "6");
+    }
+
+    #[test]
+    fn example_part_2() {
+        const EXAMPLE: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+        let input = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(Day8::part_2(&input).unwrap().to_string(), "6");
+    }
+
+    /// The first ghost alternates between 11Z and 22Z, so it's on a Z node after 2, 3, 5, 6...
+    /// steps, while the second one goes back to `back_to` after reaching 33Z
+    fn two_ghosts(back_to: &str) -> String {
+        format!(
+            "\
+L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (22Z, 22Z)
+22Z = (11B, 11B)
+33A = (33B, 33B)
+33B = (33C, 33C)
+33C = (33D, 33D)
+33D = (33Z, 33Z)
+33Z = ({back_to}, {back_to})"
+        )
+    }
+
+    #[test]
+    fn several_z_nodes_in_a_loop() {
+        // The second ghost is on 33Z after 4, 8, 12... steps
+        let input = Day8::parse(&two_ghosts("33B")).unwrap();
+        assert_eq!(Day8::part_2(&input).unwrap().to_string(), "8");
+
+        // And now after 4, 7, 10... steps, which the first ghost never is on a Z node after
+        let input = Day8::parse(&two_ghosts("33C")).unwrap();
+        assert!(Day8::part_2(&input).is_err());
+    }
+}
@@ -1,22 +1,34 @@
-pub fn solve() -> anyhow::Result<()> {
-    let content = std::fs::read_to_string("res/day09.txt")?;
+use crate::parse;
+use crate::solver::Solver;
 
-    let mut part_1 = 0;
-    let mut part_2 = 0;
-    for line in content.lines() {
-        let mut series = line
-            .split_whitespace()
-            .map(|s| s.parse())
-            .collect::<Result<Vec<_>, _>>()?;
+pub struct Day9;
 
-        part_1 += extrapolate(&series);
+impl Solver for Day9 {
+    type Input = Vec<Vec<Reading>>;
 
-        series.reverse();
-        part_2 += extrapolate(&series);
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let report = parse::lines(input, parse::numbers)?;
+
+        Ok(report)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        Ok(input
+            .iter()
+            .map(|series| extrapolate(series))
+            .sum::<Reading>())
     }
 
-    println!("Part 1: {part_1}\nPart 2: {part_2}");
-    Ok(())
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl std::fmt::Display> {
+        let mut part_2 = 0;
+        for series in input.iter() {
+            let mut series = series.clone();
+            series.reverse();
+            part_2 += extrapolate(&series);
+        }
+
+        Ok(part_2)
+    }
 }
 
 type Reading = i32;
@@ -40,6 +52,19 @@ fn extrapolate(series: &[Reading]) -> Reading {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    #[test]
+    fn example() {
+        let input = Day9::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Day9::part_1(&input).unwrap().to_string(), "114");
+        assert_eq!(Day9::part_2(&input).unwrap().to_string(), "2");
+    }
+
     #[test]
     fn extrapolation() {
         assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15]), 18);
@@ -0,0 +1,71 @@
+//! Diagnostic output of solvers, written to stderr unless captured by the running thread
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Writes a line of diagnostic output, see [`write_line`]
+#[macro_export]
+macro_rules! diag {
+    () => {
+        $crate::diagnostics::write_line(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::diagnostics::write_line(format_args!($($arg)*))
+    };
+}
+
+/// Writes `line` to the current thread's capture buffer if it's capturing, or to stderr otherwise
+pub fn write_line(line: std::fmt::Arguments) {
+    CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(captured) => {
+            let _ = writeln!(captured, "{line}");
+        }
+        None => eprintln!("{line}"),
+    });
+}
+
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Asks solvers that can explain their answers in detail to do so, for `--explain`
+pub fn enable_explain() {
+    EXPLAIN.store(true, Ordering::Relaxed);
+}
+
+/// Whether solvers should write detailed explanations of their answers as diagnostic output
+pub fn explaining() -> bool {
+    EXPLAIN.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, capturing the diagnostic output it writes from the current thread instead of
+/// writing it to stderr
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let previous = CAPTURED.replace(Some(String::new()));
+    let ret = f();
+    let captured = CAPTURED.replace(previous).unwrap_or_default();
+
+    (ret, captured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_lines() {
+        let (ret, captured) = capture(|| {
+            crate::diag!("first {}", 1);
+            crate::diag!();
+            crate::diag!("last");
+            42
+        });
+
+        assert_eq!(ret, 42);
+        assert_eq!(captured, "first 1\n\nlast\n");
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+/// Environment variable overriding the directory default inputs are read from
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Directory default inputs are read from, relative to the working directory
+const DEFAULT_INPUT_DIR: &str = "res";
+
+/// Where to read a day's puzzle input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `dayNN.txt` in `AOC_INPUT_DIR`, or in `res/` if that's not set
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+impl From<&str> for Source {
+    fn from(arg: &str) -> Self {
+        match arg {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+}
+
+impl Source {
+    pub fn read(&self, day: u32) -> anyhow::Result<String> {
+        match self {
+            Self::Default => read_file(default_path(day)),
+            Self::File(path) => read_file(path.clone()),
+            Self::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("couldn't read input from stdin")?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// Default input file of `day`, taking `AOC_INPUT_DIR` into account
+pub fn default_path(day: u32) -> PathBuf {
+    let dir = std::env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR));
+
+    dir.join(format!("day{day:02}.txt"))
+}
+
+fn read_file(path: PathBuf) -> anyhow::Result<String> {
+    std::fs::read_to_string(&path)
+        .with_context(|| format!("couldn't read input file `{}`", path.display()))
+}
@@ -0,0 +1,62 @@
+//! Solutions for [advent of code 2023](https://adventofcode.com/2023), along with the grid,
+//! search, cycle and number helpers they share
+
+pub mod bench;
+pub mod cycle;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod diagnostics;
+pub mod input;
+pub mod manhattan;
+pub mod parse;
+pub mod render;
+pub mod report;
+pub mod solver;
+pub mod utils;
+pub mod verify;
+
+use solver::Day;
+
+/// Every solved day, in calendar order
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::Day1>(1),
+    Day::new::<day2::Day2>(2),
+    Day::new::<day3::Day3>(3),
+    Day::new::<day4::Day4>(4),
+    Day::new::<day5::Day5>(5),
+    Day::new::<day6::Day6>(6),
+    Day::new::<day7::Day7>(7),
+    Day::new::<day8::Day8>(8),
+    Day::new::<day9::Day9>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day19::Day19>(19),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+];
@@ -1,65 +1,263 @@
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod manhattan;
-mod utils;
+use std::path::PathBuf;
+
+use adventofcode2023::bench;
+use adventofcode2023::diagnostics;
+use adventofcode2023::input::Source;
+use adventofcode2023::render;
+use adventofcode2023::report;
+use adventofcode2023::report::Format;
+use adventofcode2023::verify;
+use adventofcode2023::DAYS;
+use anyhow::Context;
 
 fn main() -> anyhow::Result<()> {
-    let day = if let Some(day) = std::env::args().nth(1).and_then(|s| s.parse::<u32>().ok()) {
-        day
-    } else {
+    let Some(args) = Args::parse(std::env::args().skip(1))? else {
         print_usage();
         return Ok(());
     };
 
-    match day {
-        1 => day1::solve()?,
-        2 => day2::solve()?,
-        3 => day3::solve()?,
-        4 => day4::solve()?,
-        5 => day5::solve()?,
-        6 => day6::solve()?,
-        7 => day7::solve()?,
-        8 => day8::solve()?,
-        9 => day9::solve()?,
-        10 => day10::solve()?,
-        11 => day11::solve()?,
-        12 => day12::solve()?,
-        13 => day13::solve()?,
-        14 => day14::solve()?,
-        15 => day15::solve()?,
-        16 => day16::solve()?,
-        17 => day17::solve()?,
-        18 => day18::solve()?,
-        19 => day19::solve()?,
-        20 => day20::solve()?,
-        21 => day21::solve()?,
-        day @ ..=25 => println!("Day {day} not unimplemented"),
-        _ => print_usage(),
+    let succeeded = if args.bench {
+        let days = match args.command {
+            Command::All | Command::Verify => DAYS.iter().collect::<Vec<_>>(),
+            Command::Day(day) => vec![DAYS
+                .iter()
+                .find(|d| d.number == day)
+                .with_context(|| format!("day {day} not implemented"))?],
+        };
+        let runs = args.runs.unwrap_or(bench::DEFAULT_RUNS);
+
+        bench::run(
+            &days,
+            &args.input,
+            runs,
+            args.baseline.as_deref(),
+            args.save_baseline.as_deref(),
+        )?
+    } else {
+        match args.command {
+            Command::All => report::solve_all(DAYS, args.format, args.jobs),
+            Command::Verify => {
+                let answers = args
+                    .answers
+                    .unwrap_or_else(|| PathBuf::from(verify::DEFAULT_ANSWERS_PATH));
+                verify::run(DAYS, &answers)?
+            }
+            Command::Day(day) => {
+                if args.visualize {
+                    render::enable();
+                }
+                if args.explain {
+                    diagnostics::enable_explain();
+                }
+
+                match DAYS.iter().find(|d| d.number == day) {
+                    Some(day) => report::solve_day(day, &args.input, args.format)?,
+                    None if (1..=25).contains(&day) => {
+                        println!("Day {day} not implemented");
+                        true
+                    }
+                    None => {
+                        print_usage();
+                        true
+                    }
+                }
+            }
+        }
+    };
+
+    if !succeeded {
+        std::process::exit(1);
     }
 
     Ok(())
 }
 
+enum Command {
+    Day(u32),
+    All,
+    Verify,
+}
+
+struct Args {
+    command: Command,
+    input: Source,
+    format: Format,
+    bench: bool,
+    runs: Option<usize>,
+    baseline: Option<PathBuf>,
+    save_baseline: Option<PathBuf>,
+    answers: Option<PathBuf>,
+    /// Number of threads to solve days on, if solving them in parallel
+    jobs: Option<usize>,
+    visualize: bool,
+    explain: bool,
+}
+
+impl Args {
+    /// Returns `None` if the arguments don't form a valid command
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<Self>> {
+        let mut command = None;
+        let mut input = None;
+        let mut format = Format::default();
+        let mut bench = false;
+        let mut runs = None;
+        let mut baseline = None;
+        let mut save_baseline = None;
+        let mut answers = None;
+        let mut parallel = false;
+        let mut jobs = None;
+        let mut visualize = false;
+        let mut explain = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => {
+                    let source = args.next().context("`--input` expects a path or `-`")?;
+                    input = Some(Source::from(source.as_str()));
+                }
+                "--format" => {
+                    format = args
+                        .next()
+                        .context("`--format` expects `text` or `json`")?
+                        .parse()?;
+                }
+                "--bench" => bench = true,
+                "--runs" => {
+                    runs = Some(
+                        args.next()
+                            .context("`--runs` expects a number of runs")?
+                            .parse()
+                            .context("`--runs` expects a number of runs")?,
+                    );
+                }
+                "--baseline" => {
+                    baseline = Some(args.next().context("`--baseline` expects a path")?.into());
+                }
+                "--save-baseline" => {
+                    save_baseline = Some(
+                        args.next()
+                            .context("`--save-baseline` expects a path")?
+                            .into(),
+                    );
+                }
+                "--answers" => {
+                    answers = Some(args.next().context("`--answers` expects a path")?.into());
+                }
+                "--parallel" => parallel = true,
+                "--jobs" => {
+                    jobs = Some(
+                        args.next()
+                            .context("`--jobs` expects a number of threads")?
+                            .parse()
+                            .context("`--jobs` expects a number of threads")?,
+                    );
+                }
+                "--visualize" => visualize = true,
+                "--explain" => explain = true,
+                "all" if command.is_none() => command = Some(Command::All),
+                "verify" if command.is_none() => command = Some(Command::Verify),
+                day if command.is_none() => match day.parse() {
+                    Ok(day) => command = Some(Command::Day(day)),
+                    Err(_) => return Ok(None),
+                },
+                _ => return Ok(None),
+            }
+        }
+
+        let Some(command) = command else {
+            return Ok(None);
+        };
+
+        if !matches!(command, Command::Day(_)) && input.is_some() {
+            anyhow::bail!("`--input` can only be used when solving a single day");
+        }
+
+        if !bench && (runs.is_some() || baseline.is_some() || save_baseline.is_some()) {
+            anyhow::bail!("`--runs`, `--baseline` and `--save-baseline` require `--bench`");
+        }
+
+        if bench && format != Format::Text {
+            anyhow::bail!("`--bench` only supports text output");
+        }
+
+        let verify = matches!(command, Command::Verify);
+        if verify && (bench || format != Format::Text) {
+            anyhow::bail!("`verify` doesn't support `--bench` or `--format`");
+        } else if !verify && answers.is_some() {
+            anyhow::bail!("`--answers` can only be used with `verify`");
+        }
+
+        if jobs.is_some() && !parallel {
+            anyhow::bail!("`--jobs` requires `--parallel`");
+        }
+
+        if parallel && (!matches!(command, Command::All) || bench) {
+            anyhow::bail!("`--parallel` can only be used with `all`");
+        }
+
+        if visualize && (!matches!(command, Command::Day(_)) || bench || format != Format::Text) {
+            anyhow::bail!("`--visualize` can only be used when solving a single day as text");
+        }
+
+        if explain && (!matches!(command, Command::Day(_)) || bench) {
+            anyhow::bail!("`--explain` can only be used when solving a single day");
+        }
+
+        let jobs = parallel.then(|| {
+            jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+        });
+
+        Ok(Some(Self {
+            command,
+            input: input.unwrap_or(Source::Default),
+            format,
+            bench,
+            runs,
+            baseline,
+            save_baseline,
+            answers,
+            jobs,
+            visualize,
+            explain,
+        }))
+    }
+}
+
 fn print_usage() {
-    println!("usage: adventofcode2023 DAY");
-    println!("    DAY   Advent calendar day [0..25] ");
+    println!("usage: adventofcode2023 DAY|all [--input PATH] [--format FORMAT]");
+    println!("       adventofcode2023 DAY --visualize [--input PATH]");
+    println!("       adventofcode2023 DAY --explain [--input PATH] [--format FORMAT]");
+    println!("       adventofcode2023 all --parallel [--jobs N] [--format FORMAT]");
+    println!("       adventofcode2023 DAY|all --bench [--runs N] [--baseline PATH]");
+    println!("                                        [--save-baseline PATH]");
+    println!("       adventofcode2023 verify [--answers PATH]");
+    println!("    DAY               Advent calendar day [1..25]");
+    println!("    all               Solve all implemented days in order");
+    println!("    verify            Solve all implemented days and compare the answers against");
+    println!("                      the expected ones");
+    println!("    --input PATH      Read the input from PATH instead of `res/dayNN.txt`, or from");
+    println!("                      stdin if PATH is `-`");
+    println!("    --format FORMAT   Print answers as `text` (default) or as `json`, one object");
+    println!("                      per day");
+    println!("    --parallel        Solve days concurrently, printing them in day order along");
+    println!("                      with the total wall-clock time");
+    println!("    --jobs N          Number of threads to solve days on in parallel (default:");
+    println!("                      number of CPUs)");
+    println!("    --bench           Solve each day N times and report min/median/max run times");
+    println!("                      of parsing and of each part");
+    println!("    --runs N          Number of runs per day when benchmarking (default: 10)");
+    println!("    --baseline PATH   Flag phases that got slower than in the baseline at PATH");
+    println!("    --save-baseline PATH");
+    println!("                      Save the median run times as a baseline at PATH");
+    println!("    --answers PATH    Read expected answers from PATH (default: answers.toml)");
+    println!("    --visualize       Draw the grids of days 10, 14, 16, 17, 18 and 21 as they are");
+    println!("                      solved, in colour when writing to a terminal");
+    println!("    --explain         Explain how the answers were found, for days that can (day 7");
+    println!("                      lists every hand in rank order), as diagnostic output");
+    println!();
+    println!("Default inputs are read from `$AOC_INPUT_DIR` instead of `res/` when it's set.");
 }
@@ -1,8 +1,31 @@
-use std::collections::BTreeSet;
-use std::collections::HashMap;
-use std::collections::HashSet;
 use std::convert::TryFrom;
 
+use crate::parse::ParseError;
+
+pub mod grid;
+pub mod hex;
+pub mod polygon;
+pub mod search;
+pub mod space;
+
+pub use grid::Grid;
+pub use hex::Hex;
+pub use hex::HexDirection;
+pub use polygon::polygon_area;
+pub use search::a_star;
+pub use search::all_shortest_paths;
+pub use search::bfs;
+pub use search::dijkstra;
+pub use search::distance_map;
+pub use search::reachable;
+pub use search::DebugHook;
+pub use search::SearchResult;
+pub use search::SearchStats;
+pub use space::neighbours3;
+pub use space::Cuboid;
+pub use space::Direction3;
+pub use space::Position3;
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Direction {
     North,
@@ -12,7 +35,7 @@ pub enum Direction {
 }
 
 impl TryFrom<char> for Direction {
-    type Error = String;
+    type Error = ParseError;
 
     fn try_from(from: char) -> Result<Self, Self::Error> {
         match from {
@@ -20,7 +43,10 @@ impl TryFrom<char> for Direction {
             'S' | 'v' => Ok(Self::South),
             'W' | '<' => Ok(Self::West),
             'E' | '>' => Ok(Self::East),
-            _ => Err(format!("`{}` is not a valid as direction", from)),
+            _ => Err(ParseError::char(
+                from,
+                format!("`{from}` is not a valid direction"),
+            )),
         }
     }
 }
@@ -90,7 +116,127 @@ pub fn neighbours(pos: Position) -> Vec<(Position, Direction)> {
     .collect()
 }
 
-/// ```
+/// One of the four cardinal and four diagonal directions
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl TryFrom<char> for Direction8 {
+    type Error = ParseError;
+
+    fn try_from(from: char) -> Result<Self, Self::Error> {
+        match from {
+            'N' | '^' | '↑' => Ok(Self::North),
+            '↗' => Ok(Self::NorthEast),
+            'E' | '>' | '→' => Ok(Self::East),
+            '↘' => Ok(Self::SouthEast),
+            'S' | 'v' | '↓' => Ok(Self::South),
+            '↙' => Ok(Self::SouthWest),
+            'W' | '<' | '←' => Ok(Self::West),
+            '↖' => Ok(Self::NorthWest),
+            _ => Err(ParseError::char(
+                from,
+                format!("`{from}` is not a valid direction"),
+            )),
+        }
+    }
+}
+
+impl From<Direction8> for char {
+    fn from(from: Direction8) -> Self {
+        match from {
+            Direction8::North => '↑',
+            Direction8::NorthEast => '↗',
+            Direction8::East => '→',
+            Direction8::SouthEast => '↘',
+            Direction8::South => '↓',
+            Direction8::SouthWest => '↙',
+            Direction8::West => '←',
+            Direction8::NorthWest => '↖',
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(from: Direction) -> Self {
+        match from {
+            Direction::North => Self::North,
+            Direction::South => Self::South,
+            Direction::East => Self::East,
+            Direction::West => Self::West,
+        }
+    }
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from north
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    /// Turns 45° counterclockwise
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 7) % 8]
+    }
+
+    /// Turns 45° clockwise
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 8]
+    }
+
+    pub fn u_turn(&self) -> Self {
+        Self::ALL[(*self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            Self::NorthEast | Self::SouthEast | Self::SouthWest | Self::NorthWest
+        )
+    }
+
+    pub fn forward_step(&self) -> Position {
+        match self {
+            Self::North => (-1, 0),
+            Self::NorthEast => (-1, 1),
+            Self::East => (0, 1),
+            Self::SouthEast => (1, 1),
+            Self::South => (1, 0),
+            Self::SouthWest => (1, -1),
+            Self::West => (0, -1),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+}
+
+/// All eight positions around `pos`, diagonal ones included, clockwise from north
+pub fn neighbours8(pos: Position) -> Vec<(Position, Direction8)> {
+    Direction8::ALL
+        .iter()
+        .map(|dir| {
+            let step = dir.forward_step();
+
+            ((pos.0 + step.0, pos.1 + step.1), *dir)
+        })
+        .collect()
+}
+
+/// ```text
 /// o--pos.0-->
 /// |
 /// pos.1
@@ -110,89 +256,38 @@ pub fn step_many(pos: Position, dir: Direction, steps: usize) -> Position {
     )
 }
 
-/// * `start`: initial state
-/// * `finished`: given a state, returns `true` if it satisfies goal
-/// * `nexts`: given a state, returns valid next states and the cost of transitions as a `usize`
-/// * `heur`: given a state, returns optimistic estimation of remaining cost (estimation <= real)
-/// * `debug`: given current state, pending states, and current costs prints a message
-pub fn a_star<S, F, N, H, D>(
-    start: S,
-    finished: F,
-    nexts: N,
-    heur: H,
-    debug: D,
-) -> Option<(Vec<S>, usize)>
-where
-    S: Clone + std::hash::Hash + PartialEq + Eq + PartialOrd + Ord + std::fmt::Debug,
-    F: Fn(&S) -> bool,
-    N: Fn(&S) -> HashSet<(S, usize)>,
-    H: Fn(&S) -> usize,
-    D: Fn(&S, &HashMap<S, usize>, &BTreeSet<(usize, S)>),
-{
-    let mut prevs: HashMap<S, S> = HashMap::new();
-    let mut dists: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
-    let mut to_visit: BTreeSet<(usize, S)> = BTreeSet::from([(0, start.clone())]);
-
-    while let Some((_, mut curr)) = to_visit.pop_first() {
-        debug(&curr, &dists, &to_visit);
-        if finished(&curr) {
-            let cost = *dists.get(&curr).unwrap();
-            let mut path = vec![curr.clone()];
-            while curr != start {
-                curr = prevs.get(&curr).unwrap().clone();
-                path.push(curr.clone());
-            }
-            path.reverse();
-            return Some((path, cost));
-        }
-
-        for (next, cost) in nexts(&curr) {
-            let dist = cost + *dists.get(&curr).unwrap();
-
-            if *dists.get(&next).unwrap_or(&usize::MAX) > dist {
-                dists.insert(next.clone(), dist);
-                prevs.insert(next.clone(), curr.clone());
-                to_visit.insert((dist + heur(&next), next));
-            }
-        }
-    }
+pub type Position = (isize, isize);
 
-    None
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-/// Calculates the area of a simple polygon (including perimeter) given its vertices
-pub fn polygon_area(points: impl Iterator<Item = Position>) -> usize {
-    let mut first = None;
-    let mut prev: Option<Position> = None;
-    let mut area = 0;
-    let mut perimeter = 0;
-
-    let mut handle_pair = |curr: Position, prev: Position| {
-        perimeter += (curr.0 - prev.0).unsigned_abs() + (curr.1 - prev.1).unsigned_abs();
-        // https://en.wikipedia.org/wiki/Shoelace_formula
-        area += (prev.0 + curr.0) * (curr.1 - prev.1);
-    };
-
-    for point in points {
-        if let Some(prev) = prev {
-            handle_pair(point, prev);
-        } else {
-            first = Some(point);
-        }
+    #[test]
+    fn direction8_turns() {
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+        assert_eq!(Direction8::SouthEast.u_turn(), Direction8::NorthWest);
 
-        prev = Some(point);
-    }
+        for dir in Direction8::ALL {
+            let step = dir.forward_step();
+            let back = dir.u_turn().forward_step();
 
-    if let Some(last) = prev {
-        if let Some(first) = first {
-            handle_pair(first, last);
+            assert_eq!((step.0 + back.0, step.1 + back.1), (0, 0));
+            assert_eq!(
+                dir.turn_right().turn_right().is_diagonal(),
+                dir.is_diagonal()
+            );
+            assert_eq!(Direction8::try_from(char::from(dir)), Ok(dir));
         }
     }
 
-    area /= 2;
+    #[test]
+    fn neighbours8_surround_the_position() {
+        let around = neighbours8((0, 0));
 
-    // https://en.wikipedia.org/wiki/Pick's_theorem
-    area.unsigned_abs() + perimeter / 2 + 1
+        assert_eq!(around.len(), 8);
+        assert!(around
+            .iter()
+            .all(|(pos, dir)| *pos != (0, 0) && *pos == dir.forward_step()));
+    }
 }
-
-pub type Position = (isize, isize);
@@ -0,0 +1,318 @@
+use std::fmt::Display;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::str::FromStr;
+
+use super::neighbours;
+use super::neighbours8;
+use super::Direction;
+use super::Direction8;
+use super::Position;
+use crate::parse;
+use crate::parse::ParseError;
+
+/// Rectangular grid of tiles, addressed by `(row, column)` positions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be as long as each other
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "grid rows differ in length"
+        );
+
+        Self {
+            cells: rows.into_iter().flatten().collect(),
+            height,
+            width,
+        }
+    }
+
+    /// Builds a grid calling `f` with the position of every tile, row by row
+    pub fn from_fn(height: usize, width: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|i| (0..width).map(move |j| (i as isize, j as isize)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            cells,
+            height,
+            width,
+        }
+    }
+
+    /// Parses a grid with one tile per character, every line being a row of the same length
+    pub fn parse_with(
+        input: &str,
+        mut parse: impl FnMut(char) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let rows = parse::chars(input, |_, _, c| parse(c))?;
+        let width = rows.first().map_or(0, Vec::len);
+
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            let line = input.lines().nth(i).unwrap_or_default();
+            let message = format!("expected {width} tiles, found {}", rows[i].len());
+
+            return Err(ParseError::end(line, message).at_line(i + 1, line));
+        }
+
+        Ok(Self::from_rows(rows))
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        (0..self.height as isize).contains(&pos.0) && (0..self.width as isize).contains(&pos.1)
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.offset(pos).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.offset(pos).map(|offset| &mut self.cells[offset])
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|i| self.row(i))
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(j < self.width, "column {j} is out of the grid");
+
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|j| self.column(j))
+    }
+
+    /// Every position in the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i as isize, j as isize)))
+    }
+
+    /// Every tile in the grid along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first tile, row by row, matching `predicate`
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, tile)| predicate(tile))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Orthogonal neighbours of `pos` within the grid, along with the direction to each of them
+    pub fn neighbours(&self, pos: Position) -> impl Iterator<Item = (Position, Direction)> + '_ {
+        neighbours(pos)
+            .into_iter()
+            .filter(|(next, _)| self.contains(*next))
+    }
+
+    /// Neighbours of `pos` within the grid, diagonal ones included, along with the direction to
+    /// each of them
+    pub fn neighbours8(&self, pos: Position) -> impl Iterator<Item = (Position, Direction8)> + '_ {
+        neighbours8(pos)
+            .into_iter()
+            .filter(|(next, _)| self.contains(*next))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    fn offset(&self, pos: Position) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.0 as usize * self.width + pos.1 as usize)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(height: usize, width: usize, tile: T) -> Self {
+        Self {
+            cells: vec![tile; height * width],
+            height,
+            width,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, turning rows into columns
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(i, j)| self[(j, i)].clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        let last_row = self.height as isize - 1;
+
+        Self::from_fn(self.width, self.height, |(i, j)| {
+            self[(last_row - j, i)].clone()
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let last_column = self.width as isize - 1;
+
+        Self::from_fn(self.width, self.height, |(i, j)| {
+            self[(j, last_column - i)].clone()
+        })
+    }
+
+    /// Mirrors the grid left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let last_column = self.width as isize - 1;
+
+        Self::from_fn(self.height, self.width, |(i, j)| {
+            self[(i, last_column - j)].clone()
+        })
+    }
+
+    /// Mirrors the grid top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let last_row = self.height as isize - 1;
+
+        Self::from_fn(self.height, self.width, |(i, j)| {
+            self[(last_row - i, j)].clone()
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is out of the grid"))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is out of the grid"))
+    }
+}
+
+/// Parses a grid with each tile's `TryFrom<char>`
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+    ParseError: From<T::Error>,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| Ok(T::try_from(c)?))
+    }
+}
+
+/// Renders the grid row by row, one line per row
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{tile}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+abc
+def";
+
+    #[test]
+    fn parse_and_index() {
+        let grid = EXAMPLE.parse::<Grid<char>>().unwrap();
+
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.position(|c| *c == 'f'), Some((1, 2)));
+        assert_eq!(grid.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let err = "abc\nde".parse::<Grid<char>>().unwrap_err();
+
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column, 2);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = EXAMPLE.parse::<Grid<char>>().unwrap();
+
+        assert_eq!(grid.rows().nth(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            ["ad", "be", "cf"]
+        );
+    }
+
+    #[test]
+    fn neighbours_stay_in_the_grid() {
+        let grid = EXAMPLE.parse::<Grid<char>>().unwrap();
+
+        let mut neighbours = grid
+            .neighbours((0, 0))
+            .map(|(pos, _)| pos)
+            .collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, [(0, 1), (1, 0)]);
+
+        assert_eq!(grid.neighbours8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 2)).count(), 3);
+    }
+
+    #[test]
+    fn transformations() {
+        let grid = EXAMPLE.parse::<Grid<char>>().unwrap();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+}
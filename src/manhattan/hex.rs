@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+
+/// One of the six directions on a grid of pointy-topped hexagons
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HexDirection {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+/// Parses the usual abbreviations, `e`, `ne`, `nw`, `w`, `sw` and `se`
+impl FromStr for HexDirection {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "e" => Self::East,
+            "ne" => Self::NorthEast,
+            "nw" => Self::NorthWest,
+            "w" => Self::West,
+            "sw" => Self::SouthWest,
+            "se" => Self::SouthEast,
+            _ => {
+                return Err(ParseError::new(
+                    s,
+                    format!("`{s}` is not a valid hex direction"),
+                ))
+            }
+        })
+    }
+}
+
+impl HexDirection {
+    /// Every direction, counterclockwise from east
+    pub const ALL: [Self; 6] = [
+        Self::East,
+        Self::NorthEast,
+        Self::NorthWest,
+        Self::West,
+        Self::SouthWest,
+        Self::SouthEast,
+    ];
+
+    /// Turns 60° counterclockwise
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 6]
+    }
+
+    /// Turns 60° clockwise
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 5) % 6]
+    }
+
+    pub fn u_turn(&self) -> Self {
+        Self::ALL[(*self as usize + 3) % 6]
+    }
+
+    pub fn forward_step(&self) -> Hex {
+        let (q, r) = match self {
+            Self::East => (1, 0),
+            Self::NorthEast => (1, -1),
+            Self::NorthWest => (0, -1),
+            Self::West => (-1, 0),
+            Self::SouthWest => (-1, 1),
+            Self::SouthEast => (0, 1),
+        };
+
+        Hex { q, r }
+    }
+}
+
+/// Hexagon on a grid of pointy-topped hexagons, in axial coordinates: `q` grows going east, and
+/// `r` going south-east
+///
+/// See <https://www.redblobgames.com/grids/hexagons/#coordinates-axial>
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Hex {
+    pub fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    pub fn step(&self, dir: HexDirection) -> Self {
+        self.step_many(dir, 1)
+    }
+
+    pub fn step_many(&self, dir: HexDirection, steps: usize) -> Self {
+        let step = dir.forward_step();
+
+        Self {
+            q: self.q + step.q * steps as isize,
+            r: self.r + step.r * steps as isize,
+        }
+    }
+
+    /// The six hexagons around this one, counterclockwise from east
+    pub fn neighbours(&self) -> [(Self, HexDirection); 6] {
+        HexDirection::ALL.map(|dir| (self.step(dir), dir))
+    }
+
+    /// Number of steps between the two hexagons
+    pub fn distance(&self, other: &Self) -> usize {
+        let dq = self.q - other.q;
+        let dr = self.r - other.r;
+
+        (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk(steps: &str) -> Hex {
+        steps
+            .split(',')
+            .map(|step| step.parse::<HexDirection>().unwrap())
+            .fold(Hex::default(), |hex, dir| hex.step(dir))
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(walk("e,e,ne").distance(&Hex::default()), 3);
+        assert_eq!(walk("e,w,ne,sw").distance(&Hex::default()), 0);
+        assert_eq!(walk("nw,sw"), Hex::new(-1, 0));
+        assert_eq!(walk("se,se,sw,sw").distance(&Hex::default()), 4);
+    }
+
+    #[test]
+    fn neighbours_are_one_step_away() {
+        let hex = Hex::new(2, -5);
+
+        for (neighbour, dir) in hex.neighbours() {
+            assert_eq!(hex.distance(&neighbour), 1);
+            assert_eq!(neighbour.step(dir.u_turn()), hex);
+            assert_eq!(dir.turn_left().turn_right(), dir);
+        }
+    }
+}
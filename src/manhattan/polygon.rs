@@ -0,0 +1,221 @@
+//! Geometry of simple polygons with integer vertices, given in order around the polygon. The last
+//! vertex connects back to the first one. Arithmetic is done in `i128`, and reports an [`Overflow`]
+//! rather than wrapping.
+
+use std::fmt::Display;
+
+use super::Position;
+use crate::utils::gcd;
+
+/// A polygon computation didn't fit in an `i128`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "polygon arithmetic overflowed")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Which way round a polygon's vertices go, as drawn with rows going down and columns going right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    Counterclockwise,
+    /// The polygon encloses no area
+    Degenerate,
+}
+
+/// Twice the polygon's area, which is an integer for integer vertices: positive if its vertices go
+/// counterclockwise, negative if they go clockwise
+///
+/// See <https://en.wikipedia.org/wiki/Shoelace_formula>
+pub fn signed_double_area(vertices: &[Position]) -> Result<i128, Overflow> {
+    edges(vertices).try_fold(0i128, |sum, (a, b)| {
+        // x1 * y2 - x2 * y1, with x being the column and y the row negated, as rows grow downwards
+        let term = (a.0 as i128 * b.1 as i128).checked_sub(b.0 as i128 * a.1 as i128);
+
+        term.and_then(|term| sum.checked_add(term)).ok_or(Overflow)
+    })
+}
+
+/// Half of [`signed_double_area`]. For a polygon drawn through the centres of tiles this is less
+/// than the number of tiles it covers, as those along its edges are only partly inside it.
+pub fn signed_area(vertices: &[Position]) -> Result<f64, Overflow> {
+    Ok(signed_double_area(vertices)? as f64 / 2.0)
+}
+
+/// Which way round the vertices go, judged by the sign of the area
+pub fn orientation(vertices: &[Position]) -> Result<Orientation, Overflow> {
+    Ok(match signed_double_area(vertices)?.signum() {
+        1 => Orientation::Counterclockwise,
+        -1 => Orientation::Clockwise,
+        _ => Orientation::Degenerate,
+    })
+}
+
+/// Number of integer points on the polygon's edges
+pub fn boundary_points(vertices: &[Position]) -> Result<u128, Overflow> {
+    edges(vertices).try_fold(0u128, |sum, (a, b)| {
+        let points = gcd(b.0.abs_diff(a.0) as u128, b.1.abs_diff(a.1) as u128);
+
+        sum.checked_add(points).ok_or(Overflow)
+    })
+}
+
+/// Number of integer points strictly inside the polygon
+///
+/// See <https://en.wikipedia.org/wiki/Pick's_theorem>
+pub fn interior_points(vertices: &[Position]) -> Result<u128, Overflow> {
+    let double_area = signed_double_area(vertices)?.unsigned_abs();
+    let boundary = boundary_points(vertices)?;
+
+    // A = I + B/2 - 1, so 2I = 2A - B + 2
+    Ok((double_area + 2).checked_sub(boundary).ok_or(Overflow)? / 2)
+}
+
+/// Calculates the area of a simple polygon (including perimeter) given its vertices: the number
+/// of integer points inside it or on its edges
+pub fn polygon_area(vertices: &[Position]) -> Result<u128, Overflow> {
+    interior_points(vertices)?
+        .checked_add(boundary_points(vertices)?)
+        .ok_or(Overflow)
+}
+
+/// Whether `point` lies on one of the polygon's edges
+pub fn on_boundary(vertices: &[Position], point: Position) -> Result<bool, Overflow> {
+    for (a, b) in edges(vertices) {
+        if on_segment(a, b, point)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Whether `point` lies inside the polygon or on its edges
+pub fn contains_point(vertices: &[Position], point: Position) -> Result<bool, Overflow> {
+    if on_boundary(vertices, point)? {
+        return Ok(true);
+    }
+
+    let (pi, pj) = (point.0 as i128, point.1 as i128);
+    let mut inside = false;
+
+    // Cast a ray from the point towards increasing columns, counting the edges it crosses
+    for (a, b) in edges(vertices) {
+        let (ai, aj, bi, bj) = (a.0 as i128, a.1 as i128, b.0 as i128, b.1 as i128);
+
+        if (ai > pi) != (bi > pi) {
+            // Column at which the edge crosses the point's row, compared without dividing
+            let lhs = (pj - aj).checked_mul(bi - ai).ok_or(Overflow)?;
+            let rhs = (pi - ai).checked_mul(bj - aj).ok_or(Overflow)?;
+            let crosses = if bi > ai { lhs < rhs } else { lhs > rhs };
+
+            inside ^= crosses;
+        }
+    }
+
+    Ok(inside)
+}
+
+fn on_segment(a: Position, b: Position, point: Position) -> Result<bool, Overflow> {
+    let (ai, aj, bi, bj) = (a.0 as i128, a.1 as i128, b.0 as i128, b.1 as i128);
+    let (pi, pj) = (point.0 as i128, point.1 as i128);
+
+    // Only points within the segment's bounding box can be on it
+    if !(ai.min(bi)..=ai.max(bi)).contains(&pi) || !(aj.min(bj)..=aj.max(bj)).contains(&pj) {
+        return Ok(false);
+    }
+
+    let lhs = (bi - ai).checked_mul(pj - aj).ok_or(Overflow)?;
+    let rhs = (pi - ai).checked_mul(bj - aj).ok_or(Overflow)?;
+
+    Ok(lhs == rhs)
+}
+
+/// Every edge of the polygon, closing it back to the first vertex
+fn edges(vertices: &[Position]) -> impl Iterator<Item = (Position, Position)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drawn clockwise: right, down, left, up
+    const SQUARE: [Position; 4] = [(0, 0), (0, 2), (2, 2), (2, 0)];
+
+    #[test]
+    fn square() {
+        assert_eq!(signed_double_area(&SQUARE), Ok(-8));
+        assert_eq!(signed_area(&SQUARE), Ok(-4.0));
+        assert_eq!(orientation(&SQUARE), Ok(Orientation::Clockwise));
+        assert_eq!(boundary_points(&SQUARE), Ok(8));
+        assert_eq!(interior_points(&SQUARE), Ok(1));
+        assert_eq!(polygon_area(&SQUARE), Ok(9));
+
+        let mut reversed = SQUARE;
+        reversed.reverse();
+        assert_eq!(orientation(&reversed), Ok(Orientation::Counterclockwise));
+        assert_eq!(polygon_area(&reversed), Ok(9));
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        let triangle = [(0, 0), (4, 0), (0, 4)];
+
+        assert_eq!(boundary_points(&triangle), Ok(12));
+        assert_eq!(interior_points(&triangle), Ok(3));
+        assert_eq!(
+            orientation(&[(0, 0), (1, 1), (2, 2)]),
+            Ok(Orientation::Degenerate)
+        );
+    }
+
+    #[test]
+    fn points_in_polygon() {
+        // An L shape, missing its top right quarter
+        let l_shape = [(0, 0), (0, 2), (2, 2), (2, 4), (4, 4), (4, 0)];
+
+        assert_eq!(contains_point(&l_shape, (1, 1)), Ok(true));
+        assert_eq!(contains_point(&l_shape, (3, 3)), Ok(true));
+        assert_eq!(contains_point(&l_shape, (1, 3)), Ok(false));
+        assert_eq!(contains_point(&l_shape, (5, 1)), Ok(false));
+
+        assert_eq!(on_boundary(&l_shape, (2, 3)), Ok(true));
+        assert_eq!(contains_point(&l_shape, (2, 3)), Ok(true));
+        assert_eq!(on_boundary(&l_shape, (1, 1)), Ok(false));
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let huge = [
+            (isize::MIN, isize::MIN),
+            (isize::MIN, isize::MAX),
+            (isize::MAX, isize::MAX),
+            (isize::MAX, isize::MIN),
+        ];
+
+        assert_eq!(signed_double_area(&huge), Err(Overflow));
+        assert_eq!(polygon_area(&huge), Err(Overflow));
+        assert_eq!(boundary_points(&huge), Ok(4 * usize::MAX as u128));
+
+        let triangle = [
+            (isize::MIN, isize::MIN),
+            (isize::MAX, isize::MIN),
+            (isize::MAX, isize::MAX),
+        ];
+        assert_eq!(
+            contains_point(&triangle, (0, isize::MAX - 1)),
+            Err(Overflow)
+        );
+        let diagonal = [(isize::MIN, isize::MIN), (isize::MAX, isize::MAX)];
+        assert_eq!(on_boundary(&diagonal, (0, isize::MAX - 1)), Err(Overflow));
+    }
+}
@@ -0,0 +1,507 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// How much work a search did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// States taken off the frontier and expanded
+    pub expanded: usize,
+    /// Largest number of states waiting on the frontier at once
+    pub max_frontier: usize,
+}
+
+/// Called with every state about to be expanded, its cost so far and the stats up to that point
+pub type DebugHook<'a, S> = &'a mut dyn FnMut(&S, usize, &SearchStats);
+
+/// Path to the first goal state found and its cost, if any, along with the search's stats
+pub type SearchResult<S> = (Option<(Vec<S>, usize)>, SearchStats);
+
+/// Breadth-first search, for when every step costs 1
+///
+/// * `start`: initial state
+/// * `finished`: given a state, returns `true` if it satisfies goal
+/// * `nexts`: given a state, returns the states one step away from it
+/// * `debug`: optionally called with every state about to be expanded
+pub fn bfs<S, I>(
+    start: S,
+    mut finished: impl FnMut(&S) -> bool,
+    mut nexts: impl FnMut(&S) -> I,
+    mut debug: Option<DebugHook<S>>,
+) -> SearchResult<S>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    let mut prevs: HashMap<S, S> = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut frontier = VecDeque::from([(start, 0)]);
+    let mut stats = SearchStats {
+        expanded: 0,
+        max_frontier: 1,
+    };
+
+    while let Some((curr, cost)) = frontier.pop_front() {
+        stats.expanded += 1;
+        if let Some(debug) = debug.as_mut() {
+            debug(&curr, cost, &stats);
+        }
+
+        if finished(&curr) {
+            return (Some((path_to(curr, &prevs), cost)), stats);
+        }
+
+        for next in nexts(&curr) {
+            if seen.insert(next.clone()) {
+                prevs.insert(next.clone(), curr.clone());
+                frontier.push_back((next, cost + 1));
+            }
+        }
+
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    (None, stats)
+}
+
+/// Dijkstra's shortest path search
+///
+/// * `start`: initial state
+/// * `finished`: given a state, returns `true` if it satisfies goal
+/// * `nexts`: given a state, returns valid next states and the cost of transitions as a `usize`
+/// * `debug`: optionally called with every state about to be expanded
+pub fn dijkstra<S, I>(
+    start: S,
+    finished: impl FnMut(&S) -> bool,
+    nexts: impl FnMut(&S) -> I,
+    debug: Option<DebugHook<S>>,
+) -> SearchResult<S>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    a_star(start, finished, nexts, |_| 0, debug)
+}
+
+/// A* shortest path search
+///
+/// * `start`: initial state
+/// * `finished`: given a state, returns `true` if it satisfies goal
+/// * `nexts`: given a state, returns valid next states and the cost of transitions as a `usize`
+/// * `heur`: given a state, returns optimistic estimation of remaining cost (estimation <= real)
+/// * `debug`: optionally called with every state about to be expanded
+pub fn a_star<S, I>(
+    start: S,
+    mut finished: impl FnMut(&S) -> bool,
+    mut nexts: impl FnMut(&S) -> I,
+    mut heur: impl FnMut(&S) -> usize,
+    mut debug: Option<DebugHook<S>>,
+) -> SearchResult<S>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut prevs: HashMap<S, S> = HashMap::new();
+    let mut dists: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut frontier = BinaryHeap::from([Queued {
+        priority: heur(&start),
+        cost: 0,
+        state: start,
+    }]);
+    let mut stats = SearchStats {
+        expanded: 0,
+        max_frontier: 1,
+    };
+
+    while let Some(Queued { cost, state, .. }) = frontier.pop() {
+        // States are queued again whenever a cheaper way to them is found, rather than updated in
+        // place, so skip the stale entries
+        if dists.get(&state).is_some_and(|dist| *dist < cost) {
+            continue;
+        }
+
+        stats.expanded += 1;
+        if let Some(debug) = debug.as_mut() {
+            debug(&state, cost, &stats);
+        }
+
+        if finished(&state) {
+            return (Some((path_to(state, &prevs), cost)), stats);
+        }
+
+        for (next, step) in nexts(&state) {
+            let dist = cost + step;
+
+            if dists.get(&next).is_none_or(|known| dist < *known) {
+                dists.insert(next.clone(), dist);
+                prevs.insert(next.clone(), state.clone());
+                frontier.push(Queued {
+                    priority: dist + heur(&next),
+                    cost: dist,
+                    state: next,
+                });
+            }
+        }
+
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    (None, stats)
+}
+
+/// Cost of reaching every state reachable from `start`, up to `max_cost` if given
+///
+/// * `start`: initial state
+/// * `nexts`: given a state, returns valid next states and the cost of transitions as a `usize`
+/// * `max_cost`: if given, states costing more than this to reach are left out
+pub fn distance_map<S, I>(
+    start: S,
+    mut nexts: impl FnMut(&S) -> I,
+    max_cost: Option<usize>,
+) -> HashMap<S, usize>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut dists: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut frontier = BinaryHeap::from([Queued {
+        priority: 0,
+        cost: 0,
+        state: start,
+    }]);
+
+    while let Some(Queued { cost, state, .. }) = frontier.pop() {
+        if dists.get(&state).is_some_and(|dist| *dist < cost) {
+            continue;
+        }
+
+        for (next, step) in nexts(&state) {
+            let dist = cost + step;
+
+            if max_cost.is_some_and(|max_cost| dist > max_cost) {
+                continue;
+            }
+
+            if dists.get(&next).is_none_or(|known| dist < *known) {
+                dists.insert(next.clone(), dist);
+                frontier.push(Queued {
+                    priority: dist,
+                    cost: dist,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    dists
+}
+
+/// States that can be reached from `start` in exactly `steps` steps
+///
+/// Every step is assumed to be reversible, so that any state reached in fewer steps can be reached
+/// again two steps later by stepping away and back: these are the states no more than `steps` steps
+/// away, an even number of steps short of it.
+///
+/// * `start`: initial state
+/// * `nexts`: given a state, returns the states one step away from it
+/// * `steps`: number of steps to take
+pub fn reachable<S, I>(start: S, mut nexts: impl FnMut(&S) -> I, steps: usize) -> HashSet<S>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    let unit_steps = |state: &S| nexts(state).into_iter().map(|next| (next, 1));
+
+    distance_map(start, unit_steps, Some(steps))
+        .into_iter()
+        .filter(|(_, dist)| (steps - dist).is_multiple_of(2))
+        .map(|(state, _)| state)
+        .collect()
+}
+
+/// Every cheapest path from `start` to a goal state, start first, along with their cost
+///
+/// The number of paths can grow exponentially with their length, so this is best kept to searches
+/// with few ties.
+///
+/// * `start`: initial state
+/// * `finished`: given a state, returns `true` if it satisfies goal
+/// * `nexts`: given a state, returns valid next states and the cost of transitions as a `usize`.
+///   Transitions may cost nothing, but paths then only ever go from a state to one expanded after
+///   it, so some equally cheap paths through zero-cost steps may be left out.
+pub fn all_shortest_paths<S, I>(
+    start: S,
+    mut finished: impl FnMut(&S) -> bool,
+    mut nexts: impl FnMut(&S) -> I,
+) -> Option<(Vec<Vec<S>>, usize)>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    // Every state on a cheapest path to each state, rather than just one of them
+    let mut prevs: HashMap<S, Vec<S>> = HashMap::new();
+    let mut dists: HashMap<S, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut expanded = HashSet::new();
+    let mut frontier = BinaryHeap::from([Queued {
+        priority: 0,
+        cost: 0,
+        state: start,
+    }]);
+    let mut goals = vec![];
+    let mut best = None;
+
+    while let Some(Queued { cost, state, .. }) = frontier.pop() {
+        if dists.get(&state).is_some_and(|dist| *dist < cost) {
+            continue;
+        }
+
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+
+        if !expanded.insert(state.clone()) {
+            continue;
+        }
+
+        if finished(&state) {
+            best = Some(cost);
+            goals.push(state);
+            continue;
+        }
+
+        for (next, step) in nexts(&state) {
+            let dist = cost + step;
+
+            match dists.get(&next) {
+                Some(known) if *known < dist => {}
+                // Predecessors are only recorded for states that haven't been expanded yet, so
+                // zero-cost steps can't make them loop
+                Some(known) if *known == dist => {
+                    if !expanded.contains(&next) {
+                        prevs.entry(next).or_default().push(state.clone());
+                    }
+                }
+                _ => {
+                    dists.insert(next.clone(), dist);
+                    prevs.insert(next.clone(), vec![state.clone()]);
+                    frontier.push(Queued {
+                        priority: dist,
+                        cost: dist,
+                        state: next,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut paths = vec![];
+    for goal in goals {
+        collect_paths(goal, &prevs, &mut vec![], &mut paths);
+    }
+
+    best.map(|best| (paths, best))
+}
+
+/// Pushes every path from the start of the search to `state` onto `paths`, followed by `suffix`
+/// in reverse
+fn collect_paths<S: Clone + Hash + Eq>(
+    state: S,
+    prevs: &HashMap<S, Vec<S>>,
+    suffix: &mut Vec<S>,
+    paths: &mut Vec<Vec<S>>,
+) {
+    suffix.push(state.clone());
+
+    match prevs.get(&state) {
+        Some(prevs_of_state) => {
+            for prev in prevs_of_state {
+                collect_paths(prev.clone(), prevs, suffix, paths);
+            }
+        }
+        None => paths.push(suffix.iter().rev().cloned().collect()),
+    }
+
+    suffix.pop();
+}
+
+/// Follows `prevs` back from `end` to the start of the search, returning the path start first
+fn path_to<S: Clone + Hash + Eq>(end: S, prevs: &HashMap<S, S>) -> Vec<S> {
+    let mut path = vec![end];
+
+    while let Some(prev) = prevs.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+/// Frontier entry, ordered so that `BinaryHeap` pops the lowest priority first
+struct Queued<S> {
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> PartialEq for Queued<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Queued<S> {}
+
+impl<S> PartialOrd for Queued<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Queued<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manhattan::Grid;
+    use crate::manhattan::Position;
+
+    const MAZE: &str = "\
+..#....
+..#.##.
+....#..
+.##.#.#
+....#..";
+
+    fn maze() -> Grid<char> {
+        MAZE.parse().unwrap()
+    }
+
+    fn open_neighbours(maze: &Grid<char>, pos: &Position) -> Vec<Position> {
+        maze.neighbours(*pos)
+            .map(|(next, _)| next)
+            .filter(|next| maze[*next] == '.')
+            .collect()
+    }
+
+    #[test]
+    fn bfs_finds_a_shortest_path() {
+        let maze = maze();
+
+        let (found, stats) = bfs(
+            (0, 0),
+            |pos| *pos == (4, 6),
+            |pos| open_neighbours(&maze, pos),
+            None,
+        );
+        let (path, cost) = found.unwrap();
+
+        assert_eq!(cost, 16);
+        assert_eq!(path.len(), 17);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 6)));
+        assert!(stats.expanded > 0);
+    }
+
+    #[test]
+    fn weighted_searches_agree() {
+        let maze = maze();
+        let goal: Position = (4, 6);
+        // Stepping down costs twice as much as any other step
+        let nexts = |pos: &Position| {
+            let pos = *pos;
+            open_neighbours(&maze, &pos)
+                .into_iter()
+                .map(move |next| (next, if next.0 > pos.0 { 2 } else { 1 }))
+        };
+        let heur = |pos: &Position| goal.0.abs_diff(pos.0) + goal.1.abs_diff(pos.1);
+
+        let (dijkstra_found, dijkstra_stats) = dijkstra((0, 0), |pos| *pos == goal, nexts, None);
+        let (a_star_found, a_star_stats) = a_star((0, 0), |pos| *pos == goal, nexts, heur, None);
+
+        assert_eq!(dijkstra_found.unwrap().1, 22);
+        assert_eq!(a_star_found.unwrap().1, 22);
+        assert!(a_star_stats.expanded <= dijkstra_stats.expanded);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let mut expanded = vec![];
+
+        let (found, stats) = bfs(
+            (0, 0),
+            |pos| *pos == (0, 3),
+            |_| vec![],
+            Some(&mut |pos, _, _| expanded.push(*pos)),
+        );
+
+        assert_eq!(found, None);
+        assert_eq!(stats.expanded, 1);
+        assert_eq!(expanded, [(0, 0)]);
+    }
+
+    #[test]
+    fn distance_map_with_limit() {
+        let maze = maze();
+        let unit_steps = |pos: &Position| open_neighbours(&maze, pos).into_iter().map(|n| (n, 1));
+
+        let dists = distance_map((0, 0), unit_steps, None);
+        assert_eq!(dists.get(&(4, 6)), Some(&16));
+        assert_eq!(dists.get(&(0, 2)), None);
+
+        let near = distance_map((0, 0), unit_steps, Some(2));
+        assert_eq!(near.len(), 5);
+        assert!(near.values().all(|dist| *dist <= 2));
+    }
+
+    #[test]
+    fn reachable_in_exact_steps() {
+        let open = Grid::new(5, 5, '.');
+        let nexts = |pos: &Position| open_neighbours(&open, pos);
+
+        // Steps that can be undone land back on the same checkerboard colour
+        let in_two = reachable((2, 2), nexts, 2);
+        assert_eq!(in_two.len(), 9);
+        assert!(in_two.contains(&(2, 2)));
+        assert!(!in_two.contains(&(2, 3)));
+
+        assert_eq!(reachable((0, 0), nexts, 3).len(), 6);
+    }
+
+    #[test]
+    fn all_shortest_paths_on_an_open_grid() {
+        let open = Grid::new(3, 3, '.');
+        let unit_steps = |pos: &Position| open_neighbours(&open, pos).into_iter().map(|n| (n, 1));
+
+        let (paths, cost) = all_shortest_paths((0, 0), |pos| *pos == (2, 2), unit_steps).unwrap();
+
+        assert_eq!(cost, 4);
+        // Two steps down and two to the right, in any order
+        assert_eq!(paths.len(), 6);
+        assert!(paths
+            .iter()
+            .all(|path| path.len() == 5 && path[0] == (0, 0) && path[4] == (2, 2)));
+        assert_eq!(paths.iter().collect::<HashSet<_>>().len(), 6);
+    }
+
+    #[test]
+    fn all_shortest_paths_with_free_steps() {
+        // 0 and 1 lead to each other for free, and back to the start too
+        let nexts = |state: &usize| match state {
+            0 => vec![(1, 0), (2, 1)],
+            1 => vec![(0, 0), (2, 1)],
+            _ => vec![],
+        };
+
+        let (paths, cost) = all_shortest_paths(0, |state| *state == 2, nexts).unwrap();
+
+        assert_eq!(cost, 1);
+        assert_eq!(paths, [vec![0, 2], vec![0, 1, 2]]);
+    }
+}
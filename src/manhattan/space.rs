@@ -0,0 +1,298 @@
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use crate::parse;
+use crate::parse::ParseError;
+
+/// Point in 3D space. `x` grows going east, `y` going south and `z` going up, so that `(y, x)`
+/// matches a 2D [`Position`](super::Position).
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Position3 {
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+
+    pub const fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Manhattan distance between the two points
+    pub fn distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn step(&self, dir: Direction3) -> Self {
+        *self + dir.forward_step()
+    }
+
+    pub fn step_many(&self, dir: Direction3, steps: usize) -> Self {
+        *self + dir.forward_step() * steps as isize
+    }
+}
+
+impl Add for Position3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign for Position3 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Position3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl SubAssign for Position3 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<isize> for Position3 {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for Position3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Parses comma-separated coordinates, e.g. `1,0,1` or `19, 13, 30`
+impl FromStr for Position3 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coords = s
+            .split(',')
+            .map(|coord| {
+                let coord = coord.trim();
+                parse::number(coord).map_err(|err| err.within(s, coord))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match coords[..] {
+            [x, y, z] => Ok(Self::new(x, y, z)),
+            _ => Err(ParseError::new(s, "expected three coordinates")),
+        }
+    }
+}
+
+/// One of the six directions along the axes
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction3 {
+    North,
+    South,
+    East,
+    West,
+    Up,
+    Down,
+}
+
+impl Direction3 {
+    pub const ALL: [Self; 6] = [
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+        Self::Up,
+        Self::Down,
+    ];
+
+    pub fn u_turn(&self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::East => Self::West,
+            Self::West => Self::East,
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+        }
+    }
+
+    pub fn forward_step(&self) -> Position3 {
+        match self {
+            Self::North => Position3::new(0, -1, 0),
+            Self::South => Position3::new(0, 1, 0),
+            Self::East => Position3::new(1, 0, 0),
+            Self::West => Position3::new(-1, 0, 0),
+            Self::Up => Position3::new(0, 0, 1),
+            Self::Down => Position3::new(0, 0, -1),
+        }
+    }
+}
+
+/// The six points sharing a face with `pos`
+pub fn neighbours3(pos: Position3) -> Vec<(Position3, Direction3)> {
+    Direction3::ALL
+        .iter()
+        .map(|dir| (pos.step(*dir), *dir))
+        .collect()
+}
+
+/// Axis-aligned box of points, bounds included
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Cuboid {
+    pub min: Position3,
+    pub max: Position3,
+}
+
+impl Cuboid {
+    /// Box spanning the two opposite corners, given in any order
+    pub fn new(a: Position3, b: Position3) -> Self {
+        Self {
+            min: Position3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Position3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// Smallest box containing every point, if there are any
+    pub fn bounding(points: impl IntoIterator<Item = Position3>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(match bounds {
+                Some(bounds) => bounds.union(&Self::new(point, point)),
+                None => Self::new(point, point),
+            })
+        })
+    }
+
+    pub fn contains(&self, pos: Position3) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
+
+    /// Points shared by both boxes, if they overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Position3::new(
+            self.min.x.max(other.min.x),
+            self.min.y.max(other.min.y),
+            self.min.z.max(other.min.z),
+        );
+        let max = Position3::new(
+            self.max.x.min(other.max.x),
+            self.max.y.min(other.max.y),
+            self.max.z.min(other.max.z),
+        );
+
+        (min.x <= max.x && min.y <= max.y && min.z <= max.z).then_some(Self { min, max })
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Smallest box containing both boxes
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Position3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Position3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    /// Number of points in the box
+    pub fn volume(&self) -> usize {
+        let size = self.max - self.min;
+
+        (size.x as usize + 1) * (size.y as usize + 1) * (size.z as usize + 1)
+    }
+
+    /// The box moved by `offset`
+    pub fn translate(&self, offset: Position3) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_distance() {
+        let a = "1, 2, 3".parse::<Position3>().unwrap();
+        let b = Position3::new(-1, 0, 7);
+
+        assert_eq!(a + b, Position3::new(0, 2, 10));
+        assert_eq!(a - b, Position3::new(2, 2, -4));
+        assert_eq!(-a * 2, Position3::new(-2, -4, -6));
+        assert_eq!(a.distance(&b), 8);
+        assert_eq!(a.step_many(Direction3::Down, 3), Position3::new(1, 2, 0));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = "1,x,3".parse::<Position3>().unwrap_err();
+        assert_eq!(err.column, 2);
+
+        assert!("1,2".parse::<Position3>().is_err());
+    }
+
+    #[test]
+    fn neighbours_share_a_face() {
+        let pos = Position3::new(4, -2, 9);
+
+        for (neighbour, dir) in neighbours3(pos) {
+            assert_eq!(pos.distance(&neighbour), 1);
+            assert_eq!(neighbour.step(dir.u_turn()), pos);
+        }
+    }
+
+    #[test]
+    fn cuboids() {
+        // Bricks A and B from the day 22 example
+        let a = Cuboid::new(Position3::new(1, 0, 1), Position3::new(1, 2, 1));
+        let b = Cuboid::new(Position3::new(0, 0, 2), Position3::new(2, 0, 2));
+
+        assert_eq!(a.volume(), 3);
+        assert!(!a.intersects(&b));
+        assert_eq!(
+            a.translate(Direction3::Up.forward_step()).intersection(&b),
+            Some(Cuboid::new(
+                Position3::new(1, 0, 2),
+                Position3::new(1, 0, 2)
+            ))
+        );
+        assert_eq!(
+            Cuboid::bounding([a.min, a.max, b.min, b.max]),
+            Some(a.union(&b))
+        );
+        assert!(a.union(&b).contains(Position3::new(2, 2, 1)));
+        assert_eq!(Cuboid::bounding([]), None);
+    }
+}
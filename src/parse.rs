@@ -0,0 +1,386 @@
+//! Errors, and helpers producing them, for parsing puzzle input
+
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Error parsing puzzle input, pointing at the offending character
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    /// 1-based line number, if the error has been placed in the input
+    pub line: Option<usize>,
+    /// 0-based offset of the offending character into `text`, in characters
+    pub column: usize,
+    /// Offending line, or fragment of it if the error hasn't been placed in the input yet
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Error about `text` as a whole, pointing at its first character
+    pub fn new(text: &str, message: impl Display) -> Self {
+        Self::at(text, 0, message)
+    }
+
+    /// Error pointing at the `column`th character of `text`
+    pub fn at(text: &str, column: usize, message: impl Display) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Error about the single character `c`
+    pub fn char(c: char, message: impl Display) -> Self {
+        Self::new(&c.to_string(), message)
+    }
+
+    /// Error about something missing from the end of `text`, pointing just past it
+    pub fn end(text: &str, message: impl Display) -> Self {
+        Self::at(text, text.chars().count(), message)
+    }
+
+    /// Moves an error raised for `fragment` into `outer`, which `fragment` is a slice of, so it
+    /// points at the same character of `outer`. The column is left as it is if `fragment` isn't
+    /// a slice of `outer`, rather than guessed from where its text appears.
+    pub fn within(mut self, outer: &str, fragment: &str) -> Self {
+        if let Some(offset) = byte_offset(outer, fragment) {
+            self.column += outer[..offset].chars().count();
+        }
+
+        self.text = outer.to_string();
+        self
+    }
+
+    /// Places the error at the 1-based `number`th input line, `line`, keeping its column. Errors
+    /// raised for a fragment of the line should be moved into it with [`within`](Self::within)
+    /// first.
+    pub fn at_line(mut self, number: usize, line: &str) -> Self {
+        self.text = line.to_string();
+        self.line = Some(number);
+        self
+    }
+
+    /// Moves an error placed in a block of the input `lines` lines down, to where the block starts
+    pub fn below(mut self, lines: usize) -> Self {
+        self.line = self.line.map(|line| line + lines);
+        self
+    }
+
+    pub fn for_day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Lets parsers whose tiles can't fail, such as plain `char`s, be used where a `ParseError` is
+/// expected
+impl From<Infallible> for ParseError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Renders the error followed by the offending text, with a caret under the offending character:
+///
+/// ```text
+/// day 4, line 3, column 15: invalid number `x3`
+///   |
+/// 3 | Card 3:  1 21 x3 59 44 | 69 82 63 72 16 21 14  1
+///   |               ^
+/// ```
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+        writeln!(f, "column {}: {}", self.column + 1, self.message)?;
+
+        let gutter = self.line.map(|l| l.to_string()).unwrap_or_default();
+        let padding = " ".repeat(gutter.len());
+
+        writeln!(f, "{padding} |")?;
+        writeln!(f, "{gutter} | {}", self.text)?;
+        write!(f, "{padding} | {}^", " ".repeat(self.column))
+    }
+}
+
+/// Parses `text` as a number, or any other `FromStr` value
+pub fn number<T>(text: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse()
+        .map_err(|err| ParseError::new(text, format!("invalid number `{text}`: {err}")))
+}
+
+/// Parses whitespace-separated numbers, or other `FromStr` values, pointing any error at the
+/// offending one
+pub fn numbers<T>(text: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.split_whitespace()
+        .map(|n| number(n).map_err(|err| err.within(text, n)))
+        .collect()
+}
+
+/// Strips `label` from the start of `line`, e.g. `Time:` from `Time:      7  15   30`
+pub fn labelled<'a>(line: &'a str, label: &str) -> Result<&'a str, ParseError> {
+    line.strip_prefix(label)
+        .ok_or_else(|| ParseError::new(line, format!("expected `{label}`")))
+}
+
+/// Parses a line of `label`led, whitespace-separated numbers, e.g. `seeds: 79 14 55 13`
+pub fn labelled_numbers<T>(line: &str, label: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let rest = labelled(line, label)?;
+
+    numbers(rest).map_err(|err| err.within(line, rest))
+}
+
+/// Splits `line` around the first `separator` into a key and a value, trimming the whitespace
+/// around both, e.g. `broadcaster` and `a, b, c` from `broadcaster -> a, b, c`
+pub fn key_value<'a>(line: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError> {
+    let (key, value) = line
+        .split_once(separator)
+        .ok_or_else(|| ParseError::end(line, format!("expected `{separator}`")))?;
+
+    Ok((key.trim(), value.trim()))
+}
+
+/// Parses every line of `input` with `parse`, placing any error at the line it was raised for
+pub fn lines<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse(line).map_err(|err| err.at_line(idx + 1, line)))
+        .collect()
+}
+
+/// Splits `input` into its blank-line-separated blocks, along with the number of input lines
+/// before each one. Lines holding nothing but whitespace count as blank and blocks are trimmed at
+/// the end, so `\r\n` line endings and trailing blank lines don't get in the way.
+pub fn split_blocks(input: &str) -> Vec<(usize, &str)> {
+    let mut blocks = vec![];
+    // First line and byte offset of the block being read, if any
+    let mut block = None;
+    let mut offset = 0;
+
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((first_line, start)) = block.take() {
+                blocks.push((first_line, input[start..offset].trim_end()));
+            }
+        } else if block.is_none() {
+            block = Some((idx, offset));
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((first_line, start)) = block {
+        blocks.push((first_line, input[start..].trim_end()));
+    }
+
+    blocks
+}
+
+/// Parses every blank-line-separated block of `input` with `parse`, placing any error at the
+/// input line it was raised for
+pub fn blocks<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    split_blocks(input)
+        .into_iter()
+        .map(|(start, block)| parse(block).map_err(|err| err.below(start)))
+        .collect()
+}
+
+/// Parses every character of `text` with `parse`, pointing any error at the character it was
+/// raised for
+pub fn each_char<T>(
+    text: &str,
+    mut parse: impl FnMut(char) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    text.chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            parse(c).map_err(|err| ParseError {
+                column: idx,
+                text: text.to_string(),
+                ..err
+            })
+        })
+        .collect()
+}
+
+/// Parses every character of every line of `input` with `parse`, which is given the row and
+/// column of the character, placing any error at the character it was raised for
+pub fn chars<T>(
+    input: &str,
+    mut parse: impl FnMut(usize, usize, char) -> Result<T, ParseError>,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    parse(i, j, c).map_err(|err| {
+                        let err = ParseError {
+                            column: j,
+                            text: line.to_string(),
+                            ..err
+                        };
+                        err.at_line(i + 1, line)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Offset of `inner` into `outer` in bytes, if `inner` is a slice of `outer`
+fn byte_offset(outer: &str, inner: &str) -> Option<usize> {
+    let offset = (inner.as_ptr() as usize).checked_sub(outer.as_ptr() as usize)?;
+
+    (offset + inner.len() <= outer.len()).then_some(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within() {
+        let line = "Card 3:  1 21 x3 59";
+        let fragment = &line[14..16];
+
+        let err = ParseError::at(fragment, 1, "invalid digit").within(line, fragment);
+
+        assert_eq!(err.column, 15);
+        assert_eq!(err.text, line);
+    }
+
+    #[test]
+    fn within_repeated_fragment() {
+        let line = "1 1 x";
+        let fragment = &line[2..3];
+
+        let err = ParseError::new(fragment, "nope").within(line, fragment);
+
+        assert_eq!(err.column, 2);
+    }
+
+    #[test]
+    fn within_copied_fragment() {
+        let line = "x 1 x";
+        let fragment = "x".to_string();
+
+        // Not a slice of the line, so there's no telling which `x` it was
+        let err = ParseError::at(&fragment, 0, "nope").within(line, &fragment);
+        assert_eq!(err.column, 0);
+
+        let err = ParseError::at("1 x", 2, "nope").at_line(3, line);
+        assert_eq!((err.line, err.column), (Some(3), 2));
+        assert_eq!(err.text, line);
+    }
+
+    #[test]
+    fn render() {
+        let err = lines("1 2\n3 x3 4", |line| {
+            line.split_whitespace()
+                .map(|n| number::<u32>(n).map_err(|e| e.within(line, n)))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_err()
+        .for_day(9);
+
+        assert_eq!(
+            err.to_string(),
+            "day 9, line 2, column 3: invalid number `x3`: invalid digit found in string\n  \
+             |\n\
+             2 | 3 x3 4\n  \
+             |   ^"
+        );
+    }
+
+    #[test]
+    fn blocks_errors_point_at_the_input_line() {
+        let err = blocks("1\n2\n\n3\nx", |block| lines(block, number::<u32>)).unwrap_err();
+
+        assert_eq!(err.line, Some(5));
+        assert_eq!(err.text, "x");
+    }
+
+    #[test]
+    fn blocks_tolerate_crlf_and_extra_blank_lines() {
+        let input = "a\r\nb  \r\n\r\n  \r\n\r\nc\r\n\r\n";
+
+        assert_eq!(split_blocks(input), vec![(0, "a\r\nb"), (5, "c")]);
+
+        let err = blocks(input, |block| lines(block, number::<u32>)).unwrap_err();
+        assert_eq!(err.line, Some(1));
+        assert_eq!(split_blocks(" \n"), vec![]);
+    }
+
+    #[test]
+    fn number_lists() {
+        assert_eq!(numbers::<i32>(" 1  -2 3 "), Ok(vec![1, -2, 3]));
+        assert_eq!(
+            labelled_numbers::<u32>("Time:  7 15", "Time:"),
+            Ok(vec![7, 15])
+        );
+
+        let err = labelled_numbers::<u32>("seeds: 79 1x", "seeds:").unwrap_err();
+        assert_eq!(err.column, 10);
+        assert_eq!(err.text, "seeds: 79 1x");
+
+        let err = labelled_numbers::<u32>("Distance: 9", "Time:").unwrap_err();
+        assert_eq!(err.message, "expected `Time:`");
+    }
+
+    #[test]
+    fn key_values() {
+        assert_eq!(
+            key_value("broadcaster -> a, b", "->"),
+            Ok(("broadcaster", "a, b"))
+        );
+
+        let err = key_value("Game 1", ":").unwrap_err();
+        assert_eq!(err.column, 6);
+    }
+
+    #[test]
+    fn chars_errors_point_at_the_character() {
+        let err = chars("..\n.#", |_, _, c| match c {
+            '.' => Ok(()),
+            c => Err(ParseError::new(&c.to_string(), format!("unexpected `{c}`"))),
+        })
+        .unwrap_err();
+
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column, 1);
+        assert_eq!(err.text, ".#");
+    }
+}
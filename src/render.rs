@@ -0,0 +1,158 @@
+//! Drawing grids in the terminal for `--visualize`, in colour when stdout is a terminal
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use colored::Colorize;
+use palette::FromColor;
+use palette::Hsv;
+use palette::Srgb;
+
+use crate::manhattan::Grid;
+use crate::manhattan::Position;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Makes [`show`] draw its pictures, using colours only if stdout is a terminal
+pub fn enable() {
+    colored::control::set_override(std::io::stdout().is_terminal());
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Prints the picture drawn by `draw` to stdout under a `title`, if visualizations are enabled.
+/// Solvers call this unconditionally, so the picture is only drawn when it's going to be shown.
+pub fn show(title: impl Display, draw: impl FnOnce() -> Picture) {
+    if enabled() {
+        println!("{title}\n{}\n", draw());
+    }
+}
+
+/// Colour for `value` on a scale going from blue at 0 to red at 1
+pub fn gradient(value: f32) -> Srgb<u8> {
+    let hue = 240.0 * (1.0 - value.clamp(0.0, 1.0));
+
+    Srgb::from_color(Hsv::new(hue, 0.8, 0.9)).into_format()
+}
+
+/// Character drawn for a single grid position, with optional foreground and background colours
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub fg: Option<Srgb<u8>>,
+    pub bg: Option<Srgb<u8>>,
+}
+
+impl Cell {
+    pub fn new(symbol: char) -> Self {
+        Self {
+            symbol,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    pub fn fg(self, color: Srgb<u8>) -> Self {
+        Self {
+            fg: Some(color),
+            ..self
+        }
+    }
+
+    pub fn bg(self, color: Srgb<u8>) -> Self {
+        Self {
+            bg: Some(color),
+            ..self
+        }
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut symbol = self.symbol.to_string().normal();
+
+        if let Some(fg) = self.fg {
+            symbol = symbol.truecolor(fg.red, fg.green, fg.blue);
+        }
+        if let Some(bg) = self.bg {
+            symbol = symbol.on_truecolor(bg.red, bg.green, bg.blue);
+        }
+
+        write!(f, "{symbol}")
+    }
+}
+
+/// Grid of cells, followed by a legend explaining them
+#[derive(Clone, Debug, PartialEq)]
+pub struct Picture {
+    cells: Grid<Cell>,
+    legend: Vec<(Cell, String)>,
+}
+
+impl Picture {
+    /// Draws every position of `grid` as the cell returned by `draw`
+    pub fn new<T>(grid: &Grid<T>, mut draw: impl FnMut(Position, &T) -> Cell) -> Self {
+        Self {
+            cells: Grid::from_fn(grid.height(), grid.width(), |pos| draw(pos, &grid[pos])),
+            legend: vec![],
+        }
+    }
+
+    /// Draws `cell` over the current one at `pos`, if `pos` is in the picture
+    pub fn set(&mut self, pos: Position, cell: Cell) {
+        if let Some(current) = self.cells.get_mut(pos) {
+            *current = cell;
+        }
+    }
+
+    /// Adds an entry to the legend explaining what `cell` stands for
+    pub fn legend(mut self, cell: Cell, meaning: impl Display) -> Self {
+        self.legend.push((cell, meaning.to_string()));
+        self
+    }
+}
+
+impl Display for Picture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cells)?;
+
+        for (cell, meaning) in self.legend.iter() {
+            write!(f, "\n{cell} {meaning}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_without_colours() {
+        colored::control::set_override(false);
+
+        let grid = "#.\n.#".parse::<Grid<char>>().unwrap();
+        let mut picture = Picture::new(&grid, |_, c| match c {
+            '#' => Cell::new('█').fg(gradient(1.0)),
+            _ => Cell::new(' '),
+        })
+        .legend(Cell::new('█').fg(gradient(1.0)), "wall");
+        picture.set((0, 1), Cell::new('@').bg(gradient(0.0)));
+        picture.set((5, 5), Cell::new('!'));
+
+        assert_eq!(picture.to_string(), "█@\n █\n█ wall");
+    }
+
+    #[test]
+    fn gradient_ends() {
+        assert_eq!(gradient(0.0).blue, 230);
+        assert_eq!(gradient(2.0), gradient(1.0));
+        assert_eq!(gradient(1.0).red, 230);
+    }
+}
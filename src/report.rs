@@ -0,0 +1,249 @@
+use std::str::FromStr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::diagnostics;
+use crate::input::Source;
+use crate::solver::Day;
+use crate::solver::Solution;
+
+/// How answers are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// `Part 1: …` / `Part 2: …` lines
+    #[default]
+    Text,
+    /// One JSON object per line and day
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "text" => Self::Text,
+            "json" => Self::Json,
+            other => anyhow::bail!("unknown format `{other}`, expected `text` or `json`"),
+        })
+    }
+}
+
+/// Outcome of solving a single day
+pub struct Report {
+    pub day: u32,
+    pub result: anyhow::Result<Solution>,
+    /// Time spent parsing and solving, excluding reading the input
+    pub elapsed: Duration,
+}
+
+impl Report {
+    pub fn to_json(&self) -> serde_json::Value {
+        let (part_1, part_2, error) = match &self.result {
+            Ok(solution) => (Some(&solution.part_1), Some(&solution.part_2), None),
+            Err(err) => (None, None, Some(format!("{err:#}"))),
+        };
+
+        serde_json::json!({
+            "day": self.day,
+            "part_1": part_1,
+            "part_2": part_2,
+            "elapsed_ms": self.elapsed.as_secs_f64() * 1_000.0,
+            "error": error,
+        })
+    }
+}
+
+/// Reads the input of `day` from `source` and solves it
+pub fn run(day: &Day, source: &Source) -> Report {
+    let input = source.read(day.number);
+
+    let start = Instant::now();
+    let result = input.and_then(|input| day.run(&input).map(|(solution, _)| solution));
+
+    Report {
+        day: day.number,
+        result,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Solves each of `days` from its default input on up to `jobs` threads, returning the reports in
+/// the same order as `days` along with the diagnostic output written while solving each one
+pub fn run_parallel(days: &[Day], jobs: usize) -> Vec<(Report, String)> {
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new((0..days.len()).map(|_| None).collect::<Vec<_>>());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days.get(idx) else {
+                    break;
+                };
+
+                let report = diagnostics::capture(|| run(day, &Source::Default));
+                reports.lock().unwrap()[idx] = Some(report);
+            });
+        }
+    });
+
+    reports
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|report| report.expect("every day is solved before the threads are joined"))
+        .collect()
+}
+
+/// Solves a single day and prints its answers in `format`. In text format a failure is returned
+/// as an error, while in JSON it's printed like any other report. Returns `false` if the day
+/// failed.
+pub fn solve_day(day: &Day, source: &Source, format: Format) -> anyhow::Result<bool> {
+    let report = run(day, source);
+
+    match format {
+        Format::Text => {
+            let solution = report.result?;
+            println!("Part 1: {}\nPart 2: {}", solution.part_1, solution.part_2);
+            Ok(true)
+        }
+        Format::Json => {
+            println!("{}", report.to_json());
+            Ok(report.result.is_ok())
+        }
+    }
+}
+
+/// Solves every one of `days` from its default input, on `jobs` threads if given, and prints the
+/// reports in day order, carrying on past failures. Returns `false` if any of them failed.
+pub fn solve_all(days: &[Day], format: Format, jobs: Option<usize>) -> bool {
+    let print = |report: &Report| match format {
+        Format::Text => {
+            println!("--- Day {} ---", report.day);
+            match &report.result {
+                Ok(solution) => {
+                    println!("Part 1: {}\nPart 2: {}", solution.part_1, solution.part_2);
+                    if jobs.is_some() {
+                        println!("Solved in {:.2?}", report.elapsed);
+                    }
+                }
+                Err(err) => println!("Failed: {err:#}"),
+            }
+            println!();
+        }
+        Format::Json => println!("{}", report.to_json()),
+    };
+
+    let reports = match jobs {
+        Some(jobs) => {
+            let start = Instant::now();
+            let reports = run_parallel(days, jobs);
+            let wall_clock = start.elapsed();
+
+            for (report, diagnostics) in reports.iter() {
+                eprint!("{diagnostics}");
+                print(report);
+            }
+
+            let sequential = reports
+                .iter()
+                .map(|(report, _)| report.elapsed)
+                .sum::<Duration>();
+            let summary = format!(
+                "Solved {} days on {jobs} thread(s) in {wall_clock:.2?}, {sequential:.2?} summed per day",
+                reports.len()
+            );
+            match format {
+                Format::Text => println!("{summary}"),
+                Format::Json => eprintln!("{summary}"),
+            }
+
+            reports.into_iter().map(|(report, _)| report).collect()
+        }
+        None => days
+            .iter()
+            .map(|day| {
+                let report = run(day, &Source::Default);
+                print(&report);
+                report
+            })
+            .collect::<Vec<_>>(),
+    };
+
+    let failures = reports
+        .into_iter()
+        .filter_map(|report| report.result.err().map(|err| (report.day, err)))
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() && format == Format::Text {
+        println!("{} of {} days failed", failures.len(), days.len());
+        println!("{:>5} | error", "day");
+        println!("{:->5}-+-{:-<40}", "", "");
+        for (day, err) in failures.iter() {
+            println!("{day:>5} | {err:#}");
+        }
+    }
+
+    failures.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_reports() {
+        let solved = Report {
+            day: 4,
+            result: Ok(Solution {
+                part_1: "13".to_string(),
+                part_2: "30".to_string(),
+            }),
+            elapsed: Duration::from_micros(1_500),
+        };
+
+        assert_eq!(
+            solved.to_json(),
+            serde_json::json!({
+                "day": 4,
+                "part_1": "13",
+                "part_2": "30",
+                "elapsed_ms": 1.5,
+                "error": null,
+            })
+        );
+
+        let failed = Report {
+            day: 5,
+            result: Err(anyhow::anyhow!("no seeds").context("day 5")),
+            elapsed: Duration::ZERO,
+        };
+
+        assert_eq!(
+            failed.to_json(),
+            serde_json::json!({
+                "day": 5,
+                "part_1": null,
+                "part_2": null,
+                "elapsed_ms": 0.0,
+                "error": "day 5: no seeds",
+            })
+        );
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!("text".parse::<Format>().unwrap(), Format::Text);
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+
+        let err = "yaml".parse::<Format>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown format `yaml`, expected `text` or `json`"
+        );
+    }
+}
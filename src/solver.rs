@@ -0,0 +1,88 @@
+use std::fmt::Display;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::parse::ParseError;
+
+/// A day's puzzle, split into parsing its input and solving each of the two parts
+pub trait Solver {
+    /// Parsed puzzle input, shared by both parts
+    type Input;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input>;
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<impl Display>;
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<impl Display>;
+}
+
+/// Answers to both parts of a day's puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub part_1: String,
+    pub part_2: String,
+}
+
+/// Time spent in each phase of solving a day
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part_1: Duration,
+    pub part_2: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_1 + self.part_2
+    }
+}
+
+/// Entry of the day registry
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u32,
+    pub solve: fn(&str) -> anyhow::Result<(Solution, Timings)>,
+}
+
+impl Day {
+    pub const fn new<S: Solver>(number: u32) -> Self {
+        Self {
+            number,
+            solve: solve::<S>,
+        }
+    }
+
+    /// Solves the day's puzzle for `input`, attaching the day to any parse error
+    pub fn run(&self, input: &str) -> anyhow::Result<(Solution, Timings)> {
+        (self.solve)(input).map_err(|mut err| {
+            if let Some(parse_err) = err.downcast_mut::<ParseError>() {
+                parse_err.day = Some(self.number);
+            }
+            err
+        })
+    }
+}
+
+/// Parses `input` and solves both parts with `S`, timing each phase
+pub fn solve<S: Solver>(input: &str) -> anyhow::Result<(Solution, Timings)> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let part_1 = S::part_1(&input)?.to_string();
+    let part_1_time = start.elapsed();
+
+    let start = Instant::now();
+    let part_2 = S::part_2(&input)?.to_string();
+    let part_2_time = start.elapsed();
+
+    Ok((
+        Solution { part_1, part_2 },
+        Timings {
+            parse,
+            part_1: part_1_time,
+            part_2: part_2_time,
+        },
+    ))
+}
@@ -1,64 +1,129 @@
-pub fn position_neighbours(
-    i: usize,
-    j: usize,
-    i_min: usize,
-    i_max: usize,
-    j_min: usize,
-    j_max: usize,
-    diagonal: bool,
-) -> impl Iterator<Item = (usize, usize)> {
-    assert!(i >= i_min);
-    assert!(i <= i_max);
-    assert!(j >= j_min);
-    assert!(j <= j_max);
-
-    let up = i.checked_sub(1).filter(|i| *i >= i_min);
-    let down = i.checked_add(1).filter(|i| *i <= i_max);
-    let left = j.checked_sub(1).filter(|j| *j >= j_min);
-    let right = j.checked_add(1).filter(|j| *j <= j_max);
-
-    [
-        diagonal
-            .then(|| up.and_then(|i| left.map(|j| (i, j))))
-            .flatten(),
-        up.map(|i| (i, j)),
-        diagonal
-            .then(|| up.and_then(|i| right.map(|j| (i, j))))
-            .flatten(),
-        left.map(|j| (i, j)),
-        right.map(|j| (i, j)),
-        diagonal
-            .then(|| down.and_then(|i| left.map(|j| (i, j))))
-            .flatten(),
-        down.map(|i| (i, j)),
-        diagonal
-            .then(|| down.and_then(|i| right.map(|j| (i, j))))
-            .flatten(),
-    ]
-    .into_iter()
-    .flatten()
+/// Greatest common divisor, with `gcd(0, 0) == 0`
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the non-negative greatest
+/// common divisor of `a` and `b`
+///
+/// See <https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm>
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
 }
 
-pub fn gcd(a: isize, b: isize) -> isize {
-    if b == 0 {
-        return a;
+/// Least common multiple, or `None` if it doesn't fit in a `u128`
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
     }
 
-    gcd(b, a % b)
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all the numbers, which is 1 if there are none
+pub fn lcm_many(nums: impl IntoIterator<Item = u128>) -> Option<u128> {
+    nums.into_iter().try_fold(1, lcm)
 }
 
-pub fn lcm(a: usize, b: usize) -> usize {
-    a * b / (gcd(a as isize, b as isize) as usize)
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, whose moduli don't need to be
+/// coprime. Returns the smallest non-negative solution along with the modulus all solutions are
+/// congruent under, or `None` if there's no solution, a modulus isn't positive or the
+/// arithmetic overflows.
+///
+/// See <https://en.wikipedia.org/wiki/Chinese_remainder_theorem>
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            if m2 <= 0 {
+                return None;
+            }
+
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2.rem_euclid(m2).checked_sub(r1)?;
+            if diff % g != 0 {
+                return None;
+            }
+
+            // x = r1 + m1 * k, where m1 * k ≡ diff (mod m2), and p inverts m1 / g modulo m2 / g
+            let m2 = m2 / g;
+            let k = (diff / g)
+                .rem_euclid(m2)
+                .checked_mul(p.rem_euclid(m2))?
+                .rem_euclid(m2);
+            let modulus = m1.checked_mul(m2)?;
+
+            Some((r1.checked_add(m1.checked_mul(k)?)? % modulus, modulus))
+        })
+}
+
+/// First time at which every cycle is at one of its events, given as `(offset, period)` for
+/// events happening at `offset`, `offset + period`, `offset + 2 * period`...
+pub fn align_cycles(cycles: &[(usize, usize)]) -> Option<u128> {
+    let (first, modulus) = crt(cycles
+        .iter()
+        .map(|&(offset, period)| (offset as i128, period as i128)))?;
+
+    // Every solution of the congruences before the latest offset is too early
+    let start = cycles.iter().map(|&(offset, _)| offset).max().unwrap_or(0) as i128;
+    let skipped = (start - first).max(0).checked_add(modulus - 1)? / modulus;
+
+    Some(first.checked_add(skipped.checked_mul(modulus)?)? as u128)
 }
 
-pub fn lcm_many(nums: &[usize]) -> usize {
-    if nums.len() < 2 {
-        panic!("Requested the least common multiple of less than 2 numbers");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(extended_gcd(-12, 18), (6, 1, 1));
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(lcm(u128::MAX, 2), None);
+        assert_eq!(lcm_many([]), Some(1));
+        assert_eq!(lcm_many([7]), Some(7));
+        assert_eq!(lcm_many([4, 6, 10]), Some(60));
     }
 
-    if nums.len() == 2 {
-        return lcm(nums[0], nums[1]);
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(-1, 4)]), Some((3, 4)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(1, 0)]), None);
     }
 
-    lcm(nums[0], lcm_many(&nums[1..]))
+    #[test]
+    fn cycles_with_offsets() {
+        assert_eq!(align_cycles(&[(2, 2), (3, 3)]), Some(6));
+        // Both cycles meet at 2 modulo 12, but the second one only starts at 14
+        assert_eq!(align_cycles(&[(2, 4), (14, 6)]), Some(14));
+        assert_eq!(align_cycles(&[(17, 4), (3, 6)]), Some(21));
+        assert_eq!(align_cycles(&[(0, 2), (1, 2)]), None);
+    }
 }
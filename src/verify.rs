@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::input::Source;
+use crate::report;
+use crate::report::Report;
+use crate::solver::Day;
+
+/// File expected answers are read from when no other is given
+pub const DEFAULT_ANSWERS_PATH: &str = "answers.toml";
+
+/// Expected answers of each day, read from a TOML file with a table per day:
+///
+/// ```toml
+/// [4]
+/// part_1 = 13
+/// part_2 = "30"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<u32, Expected>);
+
+/// Expected answers of a day, either of which may not be known yet
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("couldn't read answers file `{}`", path.display()))?;
+
+        Self::parse(&content).with_context(|| format!("invalid answers file `{}`", path.display()))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let table = content.parse::<toml::Table>()?;
+
+        let mut answers = BTreeMap::new();
+        for (key, value) in table {
+            let day = key
+                .parse()
+                .with_context(|| format!("`{key}` is not a day number"))?;
+            let parts = value
+                .as_table()
+                .with_context(|| format!("day {day} is not a table"))?;
+
+            let part = |name: &str| -> anyhow::Result<Option<String>> {
+                Ok(match parts.get(name) {
+                    None => None,
+                    Some(toml::Value::String(answer)) => Some(answer.clone()),
+                    Some(toml::Value::Integer(answer)) => Some(answer.to_string()),
+                    Some(other) => anyhow::bail!(
+                        "day {day} {name} should be a string or integer, found {}",
+                        other.type_str()
+                    ),
+                })
+            };
+
+            let expected = Expected {
+                part_1: part("part_1")?,
+                part_2: part("part_2")?,
+            };
+
+            answers.insert(day, expected);
+        }
+
+        Ok(Self(answers))
+    }
+}
+
+/// Solves each of `days` from its default input and prints how the answers compare against the
+/// expected ones at `answers`. Returns `false` if any answer is wrong or any day failed.
+pub fn run(days: &[Day], answers: &Path) -> anyhow::Result<bool> {
+    let answers = Answers::load(answers)?;
+
+    let reports = days
+        .iter()
+        .map(|day| report::run(day, &Source::Default))
+        .collect::<Vec<_>>();
+
+    Ok(print(&reports, &answers))
+}
+
+/// Result of comparing an answer against the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No expected answer is known
+    Missing,
+    /// The day couldn't be solved
+    Error(String),
+}
+
+impl Outcome {
+    fn new(expected: Option<&String>, actual: &str) -> Self {
+        match expected {
+            Some(expected) if expected == actual => Self::Pass,
+            Some(expected) => Self::Fail {
+                expected: expected.clone(),
+                actual: actual.to_string(),
+            },
+            None => Self::Missing,
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected}, got {actual})")
+            }
+            Self::Missing => write!(f, "missing"),
+            Self::Error(err) => write!(f, "ERROR ({err})"),
+        }
+    }
+}
+
+/// Compares the answers of a solved day against the expected ones
+pub fn check(report: &Report, answers: &Answers) -> (Outcome, Outcome) {
+    let solution = match &report.result {
+        Ok(solution) => solution,
+        Err(err) => {
+            let outcome = Outcome::Error(format!("{err:#}"));
+            return (outcome.clone(), outcome);
+        }
+    };
+
+    let expected = answers.0.get(&report.day);
+
+    (
+        Outcome::new(expected.and_then(|e| e.part_1.as_ref()), &solution.part_1),
+        Outcome::new(expected.and_then(|e| e.part_2.as_ref()), &solution.part_2),
+    )
+}
+
+/// Prints how each report compares against the expected answers, followed by a summary. Returns
+/// `true` if no answer was wrong and no day failed.
+pub fn print(reports: &[Report], answers: &Answers) -> bool {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut missing = 0;
+    let mut errors = 0;
+
+    println!("{:>5} | {:<30} | part 2", "day", "part 1");
+    println!("{:->5}-+-{:-<30}-+-{:-<30}", "", "", "");
+
+    for report in reports {
+        let (part_1, part_2) = check(report, answers);
+
+        if matches!(part_1, Outcome::Error(_)) {
+            errors += 1;
+            println!("{:>5} | {part_1}", report.day);
+            continue;
+        }
+
+        for outcome in [&part_1, &part_2] {
+            match outcome {
+                Outcome::Pass => passed += 1,
+                Outcome::Fail { .. } => failed += 1,
+                Outcome::Missing => missing += 1,
+                Outcome::Error(_) => unreachable!("both parts fail together"),
+            }
+        }
+
+        println!("{:>5} | {:<30} | {part_2}", report.day, part_1.to_string());
+    }
+
+    println!();
+    println!("{passed} passed, {failed} failed, {missing} missing, {errors} day(s) errored");
+
+    failed == 0 && errors == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solution;
+
+    #[test]
+    fn outcomes() {
+        let answers = Answers::parse(
+            r#"
+                [4]
+                part_1 = 13
+                part_2 = "30"
+
+                [5]
+                part_1 = 35
+            "#,
+        )
+        .unwrap();
+
+        let report = |day, part_1: &str, part_2: &str| Report {
+            day,
+            result: Ok(Solution {
+                part_1: part_1.to_string(),
+                part_2: part_2.to_string(),
+            }),
+            elapsed: Default::default(),
+        };
+
+        assert_eq!(
+            check(&report(4, "13", "30"), &answers),
+            (Outcome::Pass, Outcome::Pass)
+        );
+        assert_eq!(
+            check(&report(5, "35", "46"), &answers),
+            (Outcome::Pass, Outcome::Missing)
+        );
+        assert_eq!(
+            check(&report(6, "288", "71503"), &answers),
+            (Outcome::Missing, Outcome::Missing)
+        );
+        assert_eq!(
+            check(&report(4, "13", "31"), &answers).1,
+            Outcome::Fail {
+                expected: "30".to_string(),
+                actual: "31".to_string()
+            }
+        );
+    }
+}
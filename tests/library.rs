@@ -0,0 +1,26 @@
+use adventofcode2023::day9::Day9;
+use adventofcode2023::manhattan::polygon_area;
+use adventofcode2023::solver::solve;
+use adventofcode2023::utils::lcm_many;
+use adventofcode2023::DAYS;
+
+#[test]
+fn registry_is_in_calendar_order() {
+    for (idx, day) in DAYS.iter().enumerate() {
+        assert_eq!(day.number as usize, idx + 1);
+    }
+}
+
+#[test]
+fn solvers_are_usable_from_outside_the_crate() {
+    let (solution, _) = solve::<Day9>("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45").unwrap();
+
+    assert_eq!(solution.part_1, "114");
+    assert_eq!(solution.part_2, "2");
+}
+
+#[test]
+fn shared_helpers_are_usable_from_outside_the_crate() {
+    assert_eq!(polygon_area(&[(0, 0), (0, 2), (2, 2), (2, 0)]), Ok(9));
+    assert_eq!(lcm_many([4, 6, 10]), Some(60));
+}